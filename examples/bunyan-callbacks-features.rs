@@ -33,6 +33,16 @@ fn logger() -> Slogger {
                 Some(Arc::new(new_logger))
             })
         })
+        // request callback as a struct implementing the RequestEnricher trait
+        .enrich_request(RequestHeaderCount)
+        // request callback as a plain async closure, given an owned copy of the request
+        .on_request_async(|logger, request| async move {
+            let new_logger = logger.new(rocket_slogger::log_fields!(
+                "field:from-async-closure" => request.headers.len(),
+            ));
+
+            Some(Arc::new(new_logger))
+        })
         // response callback by function name
         .on_response(response_logger_callback)
         // callback as a closure function
@@ -57,11 +67,33 @@ fn logger() -> Slogger {
     todo!("Re-run this example with `--features bunyan,callbacks`")
 }
 
+// any struct can be a callback by implementing the RequestEnricher or ResponseEnricher trait
+#[cfg(feature = "callbacks")]
+struct RequestHeaderCount;
+
+#[cfg(feature = "callbacks")]
+#[rocket::async_trait]
+impl rocket_slogger::callbacks::RequestEnricher for RequestHeaderCount {
+    async fn enrich(
+        &self,
+        logger: Arc<rocket_slogger::Logger>,
+        request: &mut rocket::Request<'_>,
+    ) -> Option<Arc<rocket_slogger::Logger>> {
+        // here any async function calls or server state can be fetched,
+        // without needing to pin or box anything
+        let new_logger = logger.new(rocket_slogger::log_fields!(
+            "field:from-struct" => request.headers().len(),
+        ));
+
+        Some(Arc::new(new_logger))
+    }
+}
+
 #[cfg(feature = "callbacks")]
 fn request_logger_callback<'r>(
     logger: Arc<rocket_slogger::Logger>,
     _request: &'r mut rocket::Request<'_>,
-) -> Pin<Box<(dyn Future<Output = Option<Arc<rocket_slogger::Logger>>> + Send + 'r)>> {
+) -> Pin<Box<dyn Future<Output = Option<Arc<rocket_slogger::Logger>>> + Send + 'r>> {
     // if you import FutureExt from the rocket or futures crate,
     // then you can avoid wrapping the async block in `Box::pin` while instead calling .boxed() on it
     use rocket::futures::FutureExt;
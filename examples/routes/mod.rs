@@ -1,4 +1,4 @@
-use rocket_slogger::{info, log_fields, Slogger};
+use rocket_slogger::{info, log_fields, RequestSloggerExt, Slogger};
 
 use rocket::{catch, get, post};
 
@@ -10,6 +10,9 @@ pub fn always_greet(log: Slogger) -> &'static str {
 
 #[post("/post")]
 pub fn always_thank(log: Slogger) -> &'static str {
+    // fields annotated here are added to the response log of this request
+    log.annotate(log_fields!("thanked" => true));
+
     info!(log, "Thanked");
     "Thank you"
 }
@@ -27,12 +30,9 @@ pub fn dynamic_path(log: Slogger, dynamic: String) -> &'static str {
 }
 
 #[catch(404)]
-pub async fn not_found(req: &rocket::Request<'_>) -> String {
-    let logger = req
-        .guard::<Slogger>()
-        .await
-        // note that you probably shouldn't .unwrap() or .expect() production code
-        .expect("Slogger should always be valid");
+pub fn not_found(req: &rocket::Request<'_>) -> String {
+    // the logger of any request can be had from the request itself
+    let logger = req.logger();
 
     // there are already logs for each user request so this is not a great use case
     info!(logger, "Confused by a user");
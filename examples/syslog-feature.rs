@@ -0,0 +1,39 @@
+mod routes;
+
+use rocket::config::Config;
+use rocket::log::LogLevel;
+use rocket::{catchers, routes, Build, Rocket};
+use rocket_slogger::Slogger;
+use routes::{always_fail, always_greet, always_thank, dynamic_path, not_found};
+
+#[cfg(feature = "syslog")]
+fn logger() -> Slogger {
+    use rocket_slogger::syslog::Transport;
+
+    Slogger::new_syslog_logger("my-app", Transport::Udp(([127, 0, 0, 1], 514).into()))
+        .expect("Syslog socket should be available")
+}
+
+#[cfg(not(feature = "syslog"))]
+fn logger() -> Slogger {
+    todo!("Re-run this example with `--features syslog`")
+}
+
+#[rocket::launch]
+async fn rocket() -> Rocket<Build> {
+    // fairing built in another function just to ensure
+    // that this example runs with the feature enabled
+    let fairing = logger();
+
+    // Turn off Rocket logging, not rocket-slogger logging.
+    let mut config = Config::from(Config::figment());
+    config.log_level = LogLevel::Off;
+
+    rocket::custom(config)
+        .attach(fairing)
+        .mount(
+            "/",
+            routes![always_greet, always_thank, always_fail, dynamic_path],
+        )
+        .register("/", catchers![not_found])
+}
@@ -13,7 +13,8 @@ async fn rocket() -> Rocket<Build> {
     let plain = PlainSyncDecorator::new(std::io::stdout());
     let logger = Logger::root(FullFormat::new(plain).build().fuse(), o!());
 
-    let fairing = Slogger::from_logger(logger);
+    // `always_fail` panics, which is logged along with the request it happened in
+    let fairing = Slogger::from_logger(logger).with_panic_hook();
 
     // Turn off Rocket logging, not rocket-slogger logging.
     let mut config = Config::from(Config::figment());
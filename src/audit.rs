@@ -0,0 +1,346 @@
+use crate::fairing::json_string;
+use rocket::http::Method;
+use rocket::Request;
+use sha2::{Digest, Sha256};
+use slog::{Drain, Key, Logger, OwnedKVList, Record, RecordStatic, Serializer, KV};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+// the previous hash of the first record of a chain that is not resumed
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+pub const HASH_KEY: &str = "hash";
+pub const PREV_HASH_KEY: &str = "prev_hash";
+
+// a channel of records kept apart from the access logs, for mutations that have to be retained longer
+#[derive(Clone)]
+pub struct Audit {
+    logger: Logger,
+    chain: Arc<Mutex<String>>,
+    methods: Vec<Method>,
+    routes: Vec<String>,
+}
+
+impl Audit {
+    // every record logged through the channel is hash chained before reaching the logger's drain
+    pub fn new(logger: Logger) -> Self {
+        let chain = Arc::new(Mutex::new(GENESIS_HASH.to_string()));
+        let drain = HashChain {
+            logger,
+            chain: chain.clone(),
+        };
+
+        Self {
+            logger: Logger::root(drain.fuse(), slog::o!()),
+            chain,
+            methods: vec![],
+            routes: vec![],
+        }
+    }
+
+    // continues the chain from the hash of the last record kept, such as from before a restart
+    pub fn resume_from(self, hash: impl Into<String>) -> Self {
+        *self
+            .chain
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = hash.into();
+        self
+    }
+
+    // requests with this method get a record when responded to
+    pub fn method(mut self, method: Method) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    // POST, PUT, PATCH and DELETE
+    pub fn mutations(self) -> Self {
+        [Method::Post, Method::Put, Method::Patch, Method::Delete]
+            .into_iter()
+            .fold(self, Self::method)
+    }
+
+    // requests handled by the route with this name get a record when responded to, whatever their method
+    pub fn route(mut self, name: impl Into<String>) -> Self {
+        self.routes.push(name.into());
+        self
+    }
+
+    // wraps the channel ahead of the hash chain, such as with the redaction of the fairing,
+    // so that what is hashed is what reaches the drain
+    #[cfg(feature = "redaction")]
+    pub(crate) fn wrap_logger(&mut self, wrap: impl FnOnce(Logger) -> Logger) {
+        self.logger = wrap(self.logger.clone());
+    }
+
+    pub(crate) fn applies_to(&self, request: &Request<'_>) -> bool {
+        self.methods.contains(&request.method())
+            || request
+                .route()
+                .and_then(|route| route.name.as_deref())
+                .is_some_and(|name| self.routes.iter().any(|route| route == name))
+    }
+
+    // with the details identifying the request, built once for each request
+    pub(crate) fn logger_for(&self, request: &Request<'_>) -> Logger {
+        struct AuditLogger(Logger);
+
+        request
+            .local_cache(|| AuditLogger(self.new_logger_for(request)))
+            .0
+            .clone()
+    }
+
+    fn new_logger_for(&self, request: &Request<'_>) -> Logger {
+        #[cfg(feature = "transactions")]
+        let logger = {
+            let transaction = crate::transaction::RequestTransaction::new().attach_on(request);
+
+            self.logger.new(slog::o!(
+                "transaction" => transaction.id_as_string(),
+            ))
+        };
+
+        #[cfg(not(feature = "transactions"))]
+        let logger = &self.logger;
+
+        let route = request
+            .route()
+            .and_then(|route| route.name.as_ref())
+            .map(|name| name.to_string());
+
+        logger.new(slog::o!(
+            "client_ip" => request.client_ip().map(|ip| ip.to_string()),
+            "route" => route,
+            "method" => request.method().as_str(),
+            "uri" => request.uri().to_string(),
+        ))
+    }
+}
+
+// a field's value as it is hashed, keeping the JSON type it was logged with
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    Null,
+    Bool(bool),
+    // as written by Rust, which for floats is the shortest decimal reading back as the same value
+    Number(String),
+    String(String),
+}
+
+impl FieldValue {
+    fn to_json(&self) -> String {
+        match self {
+            Self::Null => "null".to_string(),
+            Self::Bool(value) => value.to_string(),
+            Self::Number(value) => value.clone(),
+            Self::String(value) => json_string(value),
+        }
+    }
+}
+
+impl From<bool> for FieldValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<u64> for FieldValue {
+    fn from(value: u64) -> Self {
+        Self::Number(value.to_string())
+    }
+}
+
+impl From<i64> for FieldValue {
+    fn from(value: i64) -> Self {
+        Self::Number(value.to_string())
+    }
+}
+
+// JSON has no infinities or NaN, which are null as in most JSON output
+impl From<f64> for FieldValue {
+    fn from(value: f64) -> Self {
+        match value.is_finite() {
+            true => Self::Number(value.to_string()),
+            false => Self::Null,
+        }
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+// the record as compact JSON with its keys sorted, which is what gets hashed:
+// `{"fields":{..},"level":"INFO","msg":"..","prev_hash":".."}`
+// where a key is given more than once the first is kept, and `hash` and `prev_hash` are left out of the fields
+pub fn canonical_record<K: AsRef<str>>(
+    prev_hash: &str,
+    level: &str,
+    message: &str,
+    fields: impl IntoIterator<Item = (K, FieldValue)>,
+) -> String {
+    let mut sorted = BTreeMap::new();
+    for (key, value) in fields {
+        let key = key.as_ref();
+        if ![HASH_KEY, PREV_HASH_KEY].contains(&key) && !sorted.contains_key(key) {
+            sorted.insert(key.to_string(), value);
+        }
+    }
+
+    let fields = sorted
+        .iter()
+        .map(|(key, value)| format!("{}:{}", json_string(key), value.to_json()))
+        .collect::<Vec<_>>();
+
+    format!(
+        r#"{{"fields":{{{}}},"level":{},"msg":{},"prev_hash":{}}}"#,
+        fields.join(","),
+        json_string(level),
+        json_string(message),
+        json_string(prev_hash),
+    )
+}
+
+// sha256 of the canonical record, as hex
+pub fn hash_record<K: AsRef<str>>(
+    prev_hash: &str,
+    level: &str,
+    message: &str,
+    fields: impl IntoIterator<Item = (K, FieldValue)>,
+) -> String {
+    Sha256::digest(canonical_record(prev_hash, level, message, fields).as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+// every key-value of a record with its type, the record's own before its logger's
+#[derive(Default)]
+struct Fields(Vec<(String, FieldValue)>);
+
+impl Fields {
+    fn of(record: &Record, values: &OwnedKVList) -> Vec<(String, FieldValue)> {
+        let mut fields = Self::default();
+
+        // errors only come from the serializer itself, which never fails
+        let _ = record.kv().serialize(record, &mut fields);
+        let _ = values.serialize(record, &mut fields);
+
+        fields.0
+    }
+
+    fn push(&mut self, key: Key, value: impl Into<FieldValue>) -> slog::Result {
+        self.0.push((key.to_string(), value.into()));
+        Ok(())
+    }
+}
+
+macro_rules! integers {
+    ($($method:ident: $type:ty),* $(,)?) => {
+        $(
+            fn $method(&mut self, key: Key, value: $type) -> slog::Result {
+                self.push(key, FieldValue::Number(value.to_string()))
+            }
+        )*
+    };
+}
+
+impl Serializer for Fields {
+    integers!(
+        emit_usize: usize,
+        emit_isize: isize,
+        emit_u8: u8,
+        emit_i8: i8,
+        emit_u16: u16,
+        emit_i16: i16,
+        emit_u32: u32,
+        emit_i32: i32,
+        emit_u64: u64,
+        emit_i64: i64,
+        emit_u128: u128,
+        emit_i128: i128,
+    );
+
+    // written as an f32, which an f64 of the same value would not be
+    fn emit_f32(&mut self, key: Key, value: f32) -> slog::Result {
+        match value.is_finite() {
+            true => self.push(key, FieldValue::Number(value.to_string())),
+            false => self.push(key, FieldValue::Null),
+        }
+    }
+
+    fn emit_f64(&mut self, key: Key, value: f64) -> slog::Result {
+        self.push(key, value)
+    }
+
+    fn emit_bool(&mut self, key: Key, value: bool) -> slog::Result {
+        self.push(key, value)
+    }
+
+    fn emit_unit(&mut self, key: Key) -> slog::Result {
+        self.push(key, FieldValue::Null)
+    }
+
+    fn emit_none(&mut self, key: Key) -> slog::Result {
+        self.push(key, FieldValue::Null)
+    }
+
+    fn emit_str(&mut self, key: Key, value: &str) -> slog::Result {
+        self.push(key, value)
+    }
+
+    fn emit_arguments(&mut self, key: Key, value: &fmt::Arguments) -> slog::Result {
+        self.push(key, value.to_string())
+    }
+}
+
+// adds the hash of each record and of the one before it, so that a record removed from the middle of the
+// logs breaks the chain
+struct HashChain {
+    logger: Logger,
+    chain: Arc<Mutex<String>>,
+}
+
+impl Drain for HashChain {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<(), slog::Never> {
+        let fields = Fields::of(record, values);
+        let message = record.msg().to_string();
+
+        // held until the record is passed on, so records reach the drain in the order they are chained
+        let mut chain = self
+            .chain
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let prev_hash = chain.clone();
+        let hash = hash_record(&prev_hash, record.level().as_str(), &message, fields);
+
+        let kv = (
+            record.kv(),
+            slog::kv!(HASH_KEY => hash.as_str(), PREV_HASH_KEY => prev_hash.as_str()),
+        );
+        let record_static = RecordStatic {
+            location: record.location(),
+            tag: record.tag(),
+            level: record.level(),
+        };
+        let chained = Record::new(&record_static, record.msg(), slog::BorrowedKV(&kv));
+
+        let result = Drain::log(&self.logger, &chained, values);
+        *chain = hash;
+        result
+    }
+}
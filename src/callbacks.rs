@@ -0,0 +1,364 @@
+use crate::fields::RequestFields;
+use crate::{Level, Logger};
+use rocket::futures::future::BoxFuture;
+use rocket::http::{ContentType, Header, HeaderMap, Method, Status};
+use rocket::{Build, Orbit, Request, Response, Rocket};
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+
+// run while Rocket ignites, before any of the fairing's own records
+pub type IgniteHandler =
+    dyn for<'r> Fn(Arc<Logger>, &'r Rocket<Build>) -> BoxFuture<'r, ()> + Send + Sync + 'static;
+
+// run at liftoff and shutdown, a returned logger is used for the fairing's records at that time
+pub type OrbitHandler = dyn for<'r> Fn(Arc<Logger>, &'r Rocket<Orbit>) -> BoxFuture<'r, Option<Arc<Logger>>>
+    + Send
+    + Sync
+    + 'static;
+
+// what a callback wants done with the record, all parts are optional and a later callback's level or
+// message replaces an earlier one's, while suppressing skips the record and any callbacks left to run
+#[derive(Clone, Default)]
+pub struct LogDecision {
+    pub logger: Option<Arc<Logger>>,
+    pub level: Option<Level>,
+    pub message: Option<String>,
+    pub suppress: bool,
+}
+
+impl LogDecision {
+    pub fn keep() -> Self {
+        Self::default()
+    }
+
+    pub fn replace(logger: Arc<Logger>) -> Self {
+        Self {
+            logger: Some(logger),
+            ..Self::default()
+        }
+    }
+
+    pub fn suppress() -> Self {
+        Self {
+            suppress: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+impl From<Option<Arc<Logger>>> for LogDecision {
+    fn from(logger: Option<Arc<Logger>>) -> Self {
+        Self {
+            logger,
+            ..Self::default()
+        }
+    }
+}
+
+// the outcome of running every logger callback, as used by the fairing
+pub(crate) struct Decided {
+    pub logger: Arc<Logger>,
+    pub level: Level,
+    pub message: String,
+}
+
+impl Decided {
+    pub fn new(logger: Arc<Logger>, message: &str) -> Self {
+        Self {
+            logger,
+            level: Level::Info,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn log<T: slog::KV>(&self, kv: T) {
+        let (logger, message) = (&self.logger, &self.message);
+
+        match self.level {
+            Level::Critical => slog::crit!(logger, "{}", message; kv),
+            Level::Error => slog::error!(logger, "{}", message; kv),
+            Level::Warning => slog::warn!(logger, "{}", message; kv),
+            Level::Info => slog::info!(logger, "{}", message; kv),
+            Level::Debug => slog::debug!(logger, "{}", message; kv),
+            Level::Trace => slog::trace!(logger, "{}", message; kv),
+        }
+    }
+
+    // `false` once the record has been suppressed
+    pub fn apply(&mut self, decision: LogDecision) -> bool {
+        if decision.suppress {
+            return false;
+        }
+
+        if let Some(logger) = decision.logger {
+            self.logger = logger;
+        }
+        if let Some(level) = decision.level {
+            self.level = level;
+        }
+        if let Some(message) = decision.message {
+            self.message = message;
+        }
+
+        true
+    }
+}
+
+// field callbacks of every enricher are run first, then the fields are added to the logger
+// all at once before any logger callbacks are run, each in the order they were added
+#[rocket::async_trait]
+pub trait RequestEnricher: Send + Sync + 'static {
+    async fn fields(&self, _fields: &RequestFields, _request: &Request<'_>) {}
+
+    // returning `None` keeps the logger as it was
+    async fn enrich(
+        &self,
+        _logger: Arc<Logger>,
+        _request: &mut Request<'_>,
+    ) -> Option<Arc<Logger>> {
+        None
+    }
+
+    // only needed to suppress the record or change its level or message, defaults to `enrich`
+    async fn decide(&self, logger: Arc<Logger>, request: &mut Request<'_>) -> LogDecision {
+        self.enrich(logger, request).await.into()
+    }
+}
+
+#[rocket::async_trait]
+pub trait ResponseEnricher: Send + Sync + 'static {
+    // the response is only borrowed mutably because it can not be shared between threads
+    async fn fields(
+        &self,
+        _fields: &RequestFields,
+        _request: &Request<'_>,
+        _response: &mut Response<'_>,
+    ) {
+    }
+
+    async fn enrich(
+        &self,
+        _logger: Arc<Logger>,
+        _request: &Request<'_>,
+        _response: &mut Response<'_>,
+    ) -> Option<Arc<Logger>> {
+        None
+    }
+
+    async fn decide(
+        &self,
+        logger: Arc<Logger>,
+        request: &Request<'_>,
+        response: &mut Response<'_>,
+    ) -> LogDecision {
+        self.enrich(logger, request, response).await.into()
+    }
+}
+
+// adapts the handlers taken by `Slogger::on_request` and `Slogger::on_response`
+pub(crate) struct BoxedRequestHandler<F>(pub F);
+
+#[rocket::async_trait]
+impl<F> RequestEnricher for BoxedRequestHandler<F>
+where
+    F: for<'r> Fn(
+            Arc<Logger>,
+            &'r mut Request<'_>,
+        ) -> Pin<Box<dyn Future<Output = Option<Arc<Logger>>> + Send + 'r>>
+        + Send
+        + Sync
+        + 'static,
+{
+    async fn enrich(&self, logger: Arc<Logger>, request: &mut Request<'_>) -> Option<Arc<Logger>> {
+        (self.0)(logger, request).await
+    }
+}
+
+pub(crate) struct BoxedResponseHandler<F>(pub F);
+
+#[rocket::async_trait]
+impl<F> ResponseEnricher for BoxedResponseHandler<F>
+where
+    F: for<'r> Fn(
+            Arc<Logger>,
+            &'r Request<'_>,
+            &'r mut Response<'_>,
+        ) -> Pin<Box<dyn Future<Output = Option<Arc<Logger>>> + Send + 'r>>
+        + Send
+        + Sync
+        + 'static,
+{
+    async fn enrich(
+        &self,
+        logger: Arc<Logger>,
+        request: &Request<'_>,
+        response: &mut Response<'_>,
+    ) -> Option<Arc<Logger>> {
+        (self.0)(logger, request, response).await
+    }
+}
+
+// owned copies of the request and response, so that plain `async move` closures can be used
+// as callbacks without borrowing from either
+#[derive(Clone, Debug)]
+pub struct RequestInfo {
+    pub method: Method,
+    pub uri: String,
+    pub route: Option<String>,
+    pub client_ip: Option<IpAddr>,
+    pub headers: HeaderMap<'static>,
+}
+
+impl RequestInfo {
+    pub fn from_request(request: &Request<'_>) -> Self {
+        Self {
+            method: request.method(),
+            uri: request.uri().to_string(),
+            route: request
+                .route()
+                .and_then(|route| route.name.as_ref())
+                .map(|name| name.to_string()),
+            client_ip: request.client_ip(),
+            headers: owned_headers(request.headers()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ResponseInfo {
+    pub request: RequestInfo,
+    pub status: Status,
+    pub content_type: Option<ContentType>,
+    pub headers: HeaderMap<'static>,
+}
+
+impl ResponseInfo {
+    pub fn from_response(request: &Request<'_>, response: &Response<'_>) -> Self {
+        Self {
+            request: RequestInfo::from_request(request),
+            status: response.status(),
+            content_type: response.content_type(),
+            headers: owned_headers(response.headers()),
+        }
+    }
+}
+
+fn owned_headers(headers: &HeaderMap<'_>) -> HeaderMap<'static> {
+    let mut owned = HeaderMap::new();
+    for header in headers.iter() {
+        owned.add(Header::new(
+            header.name().as_str().to_string(),
+            header.value().to_string(),
+        ));
+    }
+    owned
+}
+
+pub(crate) struct AsyncRequestHandler<F>(pub F);
+
+#[rocket::async_trait]
+impl<F, Fut> RequestEnricher for AsyncRequestHandler<F>
+where
+    F: Fn(Arc<Logger>, RequestInfo) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Option<Arc<Logger>>> + Send + 'static,
+{
+    async fn enrich(&self, logger: Arc<Logger>, request: &mut Request<'_>) -> Option<Arc<Logger>> {
+        (self.0)(logger, RequestInfo::from_request(request)).await
+    }
+}
+
+pub(crate) struct AsyncResponseHandler<F>(pub F);
+
+#[rocket::async_trait]
+impl<F, Fut> ResponseEnricher for AsyncResponseHandler<F>
+where
+    F: Fn(Arc<Logger>, ResponseInfo) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Option<Arc<Logger>>> + Send + 'static,
+{
+    async fn enrich(
+        &self,
+        logger: Arc<Logger>,
+        request: &Request<'_>,
+        response: &mut Response<'_>,
+    ) -> Option<Arc<Logger>> {
+        (self.0)(logger, ResponseInfo::from_response(request, response)).await
+    }
+}
+
+pub(crate) struct AsyncRequestFieldsHandler<F>(pub F);
+
+#[rocket::async_trait]
+impl<F, Fut> RequestEnricher for AsyncRequestFieldsHandler<F>
+where
+    F: Fn(RequestFields, RequestInfo) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    async fn fields(&self, fields: &RequestFields, request: &Request<'_>) {
+        (self.0)(fields.clone(), RequestInfo::from_request(request)).await
+    }
+}
+
+pub(crate) struct AsyncResponseFieldsHandler<F>(pub F);
+
+#[rocket::async_trait]
+impl<F, Fut> ResponseEnricher for AsyncResponseFieldsHandler<F>
+where
+    F: Fn(RequestFields, ResponseInfo) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    async fn fields(
+        &self,
+        fields: &RequestFields,
+        request: &Request<'_>,
+        response: &mut Response<'_>,
+    ) {
+        (self.0)(
+            fields.clone(),
+            ResponseInfo::from_response(request, response),
+        )
+        .await
+    }
+}
+
+pub(crate) struct AsyncRequestDecisionHandler<F>(pub F);
+
+#[rocket::async_trait]
+impl<F, Fut> RequestEnricher for AsyncRequestDecisionHandler<F>
+where
+    F: Fn(Arc<Logger>, RequestInfo) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = LogDecision> + Send + 'static,
+{
+    async fn decide(&self, logger: Arc<Logger>, request: &mut Request<'_>) -> LogDecision {
+        (self.0)(logger, RequestInfo::from_request(request)).await
+    }
+}
+
+pub(crate) struct AsyncResponseDecisionHandler<F>(pub F);
+
+#[rocket::async_trait]
+impl<F, Fut> ResponseEnricher for AsyncResponseDecisionHandler<F>
+where
+    F: Fn(Arc<Logger>, ResponseInfo) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = LogDecision> + Send + 'static,
+{
+    async fn decide(
+        &self,
+        logger: Arc<Logger>,
+        request: &Request<'_>,
+        response: &mut Response<'_>,
+    ) -> LogDecision {
+        (self.0)(logger, ResponseInfo::from_response(request, response)).await
+    }
+}
@@ -0,0 +1,536 @@
+use crate::fairing::json_option;
+use crate::kv::strip_ansi_codes;
+use rocket::http::Status;
+use rocket::tokio::task::{self, Id};
+use rocket::{Catcher, Request, Route};
+use slog::{Logger, Record, Serializer, KV};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::thread::{self, ThreadId};
+
+// what Rocket did with a request between the request and response fairings, which it only reports through
+// its own `log` records, so it is learnt from those by the log bridge when installed
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Dispatch {
+    caught: Option<Caught>,
+    status: Option<u16>,
+    failed_guard: Option<String>,
+    forwarded: bool,
+    panicked: bool,
+    attempts: Vec<Attempt>,
+    // for records about the request made outside of it, such as from the panic hook
+    logger: Option<Arc<Logger>>,
+    route: Option<String>,
+    // the request span, for `tracing` events of the request made outside of it
+    #[cfg(feature = "tracing")]
+    span: Option<::tracing::span::Id>,
+}
+
+// a route Rocket tried for the request, as displayed by Rocket
+#[derive(Clone, Debug)]
+struct Attempt {
+    route: String,
+    forward: Option<String>,
+    status: Option<u16>,
+}
+
+#[derive(Clone, Debug)]
+struct Caught {
+    // `None` for catchers registered without a name
+    name: Option<String>,
+    // `None` for default catchers
+    code: Option<u16>,
+}
+
+// Rocket runs the request fairings, the routes and the response fairings of a request in a single task,
+// while `rocket::local` clients dispatch outside of any task, on the thread awaiting the response, so
+// requests dispatched concurrently by one thread outside of tasks only keep the details of the latest
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Key {
+    Task(Id),
+    Thread(ThreadId),
+}
+
+impl Key {
+    fn current() -> Self {
+        match task::try_id() {
+            Some(id) => Self::Task(id),
+            None => Self::Thread(thread::current().id()),
+        }
+    }
+}
+
+// set once anything reads the registry, such as the log bridge, the panic hook or the tracing layer,
+// as until then requests are not registered at all
+static READ: AtomicBool = AtomicBool::new(false);
+
+// each entry is tagged with the request it was made for, as a thread can go on to dispatch other requests
+// while the earlier ones are still alive, such as responses kept around by tests
+fn registry() -> &'static Mutex<HashMap<Key, (u64, Dispatch)>> {
+    static DISPATCHES: OnceLock<Mutex<HashMap<Key, (u64, Dispatch)>>> = OnceLock::new();
+
+    DISPATCHES.get_or_init(Default::default)
+}
+
+fn dispatches() -> MutexGuard<'static, HashMap<Key, (u64, Dispatch)>> {
+    registry()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// the request's entry, removed along with the request if the response fairings never run,
+// such as when the client disconnects
+struct Registered(Option<(Key, u64)>);
+
+impl Registered {
+    fn of(request: &Request<'_>) -> Option<(Key, u64)> {
+        request.local_cache(|| Registered(None)).0
+    }
+}
+
+impl Drop for Registered {
+    fn drop(&mut self) {
+        if let Some((key, serial)) = self.0 {
+            let mut dispatches = dispatches();
+            if dispatches
+                .get(&key)
+                .is_some_and(|(entry, _)| *entry == serial)
+            {
+                dispatches.remove(&key);
+            }
+        }
+    }
+}
+
+impl Dispatch {
+    pub fn enable() {
+        READ.store(true, Ordering::Relaxed);
+    }
+
+    pub fn begin(request: &Request<'_>) {
+        static SERIAL: AtomicU64 = AtomicU64::new(0);
+
+        if !READ.load(Ordering::Relaxed) {
+            return;
+        }
+
+        request.local_cache(|| {
+            let key = Key::current();
+            let serial = SERIAL.fetch_add(1, Ordering::Relaxed);
+            dispatches().insert(key, (serial, Dispatch::default()));
+
+            Registered(Some((key, serial)))
+        });
+    }
+
+    pub fn attach_logger(request: &Request<'_>, logger: Arc<Logger>) {
+        Self::update(request, |dispatch| dispatch.logger = Some(logger));
+    }
+
+    pub fn attach_route(request: &Request<'_>, route: &Route) {
+        let name = route.name.as_ref().map(|name| name.to_string());
+        Self::update(request, |dispatch| dispatch.route = name);
+    }
+
+    #[cfg(feature = "tracing")]
+    pub fn attach_span(request: &Request<'_>, span: ::tracing::span::Id) {
+        Self::update(request, |dispatch| dispatch.span = Some(span));
+    }
+
+    fn update(request: &Request<'_>, update: impl FnOnce(&mut Dispatch)) {
+        if let Some((key, serial)) = Registered::of(request) {
+            if let Some((_, dispatch)) = dispatches()
+                .get_mut(&key)
+                .filter(|(entry, _)| *entry == serial)
+            {
+                update(dispatch);
+            }
+        }
+    }
+
+    // the logger and route of the request being handled by the current task or thread, without waiting on the
+    // registry as this can be called while it is locked, such as when panicking
+    pub fn current() -> Option<(Arc<Logger>, Option<String>)> {
+        let dispatches = registry().try_lock().ok()?;
+        let (_, dispatch) = dispatches.get(&Key::current())?;
+
+        Some((dispatch.logger.clone()?, dispatch.route.clone()))
+    }
+
+    // the request span of the request being handled by the current task or thread, as for `current`
+    #[cfg(feature = "tracing")]
+    pub fn current_span() -> Option<::tracing::span::Id> {
+        let dispatches = registry().try_lock().ok()?;
+        let (_, dispatch) = dispatches.get(&Key::current())?;
+
+        dispatch.span.clone()
+    }
+
+    pub fn finish(request: &Request<'_>) -> Self {
+        let Some((key, serial)) = Registered::of(request) else {
+            return Self::default();
+        };
+
+        let mut dispatches = dispatches();
+        let dispatch = match dispatches.get(&key) {
+            Some((entry, _)) if *entry == serial => dispatches
+                .remove(&key)
+                .map(|(_, dispatch)| dispatch)
+                .unwrap_or_default(),
+            _ => Self::default(),
+        };
+
+        dispatch.understood(request)
+    }
+
+    // Rocket's messages are read as best as can be, so unless one was understood for the route that handled
+    // the request, or none was for a request that matched no route, what was made of them is dropped for
+    // the details that can be had without them
+    fn understood(self, request: &Request<'_>) -> Self {
+        let understood = match request.route() {
+            Some(route) => self
+                .attempts
+                .last()
+                .is_some_and(|attempt| strip_ansi_codes(&route.to_string()) == attempt.route),
+            None => self.attempts.is_empty(),
+        };
+
+        match understood {
+            true => self,
+            false => Self {
+                logger: self.logger,
+                route: self.route,
+                #[cfg(feature = "tracing")]
+                span: self.span,
+                ..Self::default()
+            },
+        }
+    }
+
+    // records Rocket's own messages about routing and catching the request of the current task
+    #[allow(dead_code)] // only used by the log bridge
+    pub fn observe(message: &str) {
+        let mut dispatches = dispatches();
+        let Some((_, dispatch)) = dispatches.get_mut(&Key::current()) else {
+            return;
+        };
+
+        let message = message.trim();
+        if let Some(route) = message.strip_prefix("Matched: ") {
+            dispatch.forwarded = false;
+            dispatch.route = route
+                .strip_prefix('(')
+                .and_then(|route| route.split_once(')'))
+                .map(|(name, _)| name.to_string());
+            dispatch.attempts.push(Attempt {
+                route: route.to_string(),
+                forward: None,
+                status: None,
+            });
+        } else if let Some(outcome) = message.strip_prefix("Outcome: ") {
+            dispatch.forwarded = outcome.starts_with("Forward");
+            dispatch.status = status_in(outcome).or(dispatch.status);
+
+            if let (true, Some(attempt)) = (dispatch.forwarded, dispatch.attempts.last_mut()) {
+                attempt.status = status_in(outcome);
+                attempt
+                    .forward
+                    .get_or_insert_with(|| "forwarded".to_string());
+            }
+        } else if let Some(reason) = forward_reason(message) {
+            if let Some(attempt) = dispatch.attempts.last_mut() {
+                attempt.forward = Some(reason);
+            }
+        } else if let Some(guard) = guard_name(message, " failed: ") {
+            dispatch.failed_guard = Some(guard);
+        } else if message == "A handler panicked."
+            || (message.starts_with("Handler ") && message.ends_with(" panicked."))
+        {
+            dispatch.panicked = true;
+        } else if let Some(catcher) = message
+            .strip_prefix("Responding with registered ")
+            .and_then(|catcher| catcher.strip_suffix(" catcher."))
+        {
+            dispatch.caught(Caught::from_display(catcher));
+        } else if let Some(code) = message
+            .strip_prefix("No ")
+            .and_then(|code| code.strip_suffix(" catcher registered. Using Rocket default."))
+        {
+            let code = code.parse().ok();
+            dispatch.status = dispatch.status.or(code);
+            dispatch.caught(Caught::rocket_default(code));
+        } else if message.ends_with(" catcher failed. Using Rocket default 500.") {
+            dispatch.caught(Caught::rocket_default(Some(500)));
+        }
+    }
+
+    // the first catcher is the one for the original error, Rocket only tries others when it fails
+    fn caught(&mut self, caught: Caught) {
+        if self.caught.is_none() {
+            self.status = self.status.or(caught.code);
+        }
+        self.caught = Some(caught);
+    }
+
+    // `None` when the response was made by a route rather than a catcher, or when that can not be told
+    pub fn catcher_fields(&self, request: &Request<'_>, status: Status) -> Option<CatcherFields> {
+        let failure = if request.route().is_none() {
+            "no_route"
+        } else if self.panicked {
+            "panic"
+        } else if self.failed_guard.is_some() {
+            "guard"
+        } else if self.forwarded {
+            "forward"
+        } else {
+            "handler"
+        };
+
+        let caught = match &self.caught {
+            Some(caught) => caught.clone(),
+            // with nothing observed, only a request that matched no routes at all is certain to have been
+            // caught, for which Rocket uses the 404 catcher
+            None if request.route().is_none() => Caught::resolve(request, Status::NotFound),
+            None => return None,
+        };
+
+        Some(CatcherFields {
+            status: self.status.unwrap_or(if failure == "no_route" {
+                404
+            } else {
+                status.code
+            }),
+            caught,
+            failure,
+            failed_guard: self.failed_guard.clone(),
+        })
+    }
+
+    // `None` unless the request was forwarded by at least one route
+    pub fn route_chain(&self, request: &Request<'_>) -> Option<RouteChain> {
+        let mut chain = if self.attempts.is_empty() {
+            // without Rocket's messages only the routes that took the logger are known, each one before
+            // the last having forwarded the request for reasons unknown
+            SeenRoutes::of(request)
+                .into_iter()
+                .map(|route| Hop {
+                    route: Some(route),
+                    forward: Some("forwarded".to_string()),
+                    status: None,
+                })
+                .collect::<Vec<_>>()
+        } else {
+            self.attempts
+                .iter()
+                .map(|attempt| Hop {
+                    route: request
+                        .rocket()
+                        .routes()
+                        .find(|route| strip_ansi_codes(&route.to_string()) == attempt.route),
+                    forward: attempt.forward.clone(),
+                    status: attempt.status,
+                })
+                .collect()
+        };
+
+        if self.attempts.is_empty() {
+            if let Some(route) = request.route() {
+                if !chain
+                    .last()
+                    .is_some_and(|hop| hop.route.is_some_and(|hop| std::ptr::eq(hop, route)))
+                {
+                    chain.push(Hop {
+                        route: Some(route),
+                        forward: None,
+                        status: None,
+                    });
+                }
+            }
+            if let Some(last) = chain.last_mut() {
+                last.forward = None;
+            }
+        }
+
+        chain
+            .iter()
+            .any(|hop| hop.forward.is_some())
+            .then(|| RouteChain(chain.iter().map(Hop::to_json).collect()))
+    }
+}
+
+struct Hop<'r> {
+    route: Option<&'r Route>,
+    forward: Option<String>,
+    status: Option<u16>,
+}
+
+impl Hop<'_> {
+    fn to_json(&self) -> String {
+        format!(
+            r#"{{"route":{},"rank":{},"path":{},"forward":{},"status":{}}}"#,
+            json_option(self.route.and_then(|route| route.name.as_deref())),
+            self.route
+                .map_or_else(|| "null".to_string(), |route| route.rank.to_string()),
+            json_option(self.route.map(|route| route.uri.to_string()).as_deref()),
+            json_option(self.forward.as_deref()),
+            self.status
+                .map_or_else(|| "null".to_string(), |status| status.to_string()),
+        )
+    }
+}
+
+// the routes a request was handed to, in the order they were tried, as a JSON array
+pub(crate) struct RouteChain(Vec<String>);
+
+impl KV for RouteChain {
+    fn serialize(&self, record: &Record, serializer: &mut dyn Serializer) -> slog::Result {
+        slog::kv!("route_chain" => format!("[{}]", self.0.join(","))).serialize(record, serializer)
+    }
+}
+
+// the routes whose request guards asked for the logger, recorded as each is handed the request
+pub(crate) struct SeenRoutes(Mutex<Vec<usize>>);
+
+impl SeenRoutes {
+    pub fn record(request: &Request<'_>, route: &Route) {
+        let seen = request.local_cache(|| Self(Mutex::new(Vec::new())));
+        let mut seen = seen
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        // only the address is kept, to be compared with the routes Rocket knows of
+        let route = route as *const Route as usize;
+        if seen.last() != Some(&route) {
+            seen.push(route);
+        }
+    }
+
+    fn of<'r>(request: &'r Request<'_>) -> Vec<&'r Route> {
+        let seen = request.local_cache(|| Self(Mutex::new(Vec::new())));
+        let seen = seen
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        seen.iter()
+            .filter_map(|seen| {
+                request
+                    .rocket()
+                    .routes()
+                    .find(|route| *route as *const Route as usize == *seen)
+            })
+            .collect()
+    }
+}
+
+impl Caught {
+    fn rocket_default(code: Option<u16>) -> Self {
+        Self {
+            name: Some("rocket_default".to_string()),
+            code,
+        }
+    }
+
+    fn from_catcher(catcher: &Catcher) -> Self {
+        Self {
+            name: catcher.name.as_ref().map(|name| name.to_string()),
+            code: catcher.code,
+        }
+    }
+
+    // as Rocket displays catchers, `(<name>) <base> <code or "default">` with the name and base optional
+    fn from_display(catcher: &str) -> Self {
+        let name = catcher
+            .strip_prefix('(')
+            .and_then(|catcher| catcher.split_once(')'))
+            .map(|(name, _)| name.to_string());
+
+        Self {
+            name,
+            code: catcher
+                .rsplit(' ')
+                .next()
+                .and_then(|code| code.parse().ok()),
+        }
+    }
+
+    // the same choice Rocket makes: the catcher with the longest base that prefixes the request path,
+    // preferring one for the status over a default catcher when their bases are as long
+    fn resolve(request: &Request<'_>, status: Status) -> Self {
+        let path = request.uri().path();
+        let matching = |code: Option<u16>| {
+            request
+                .rocket()
+                .catchers()
+                .filter(|catcher| catcher.code == code)
+                .filter(|catcher| catcher.base.path().segments().prefix_of(path.segments()))
+                .max_by_key(|catcher| catcher.base.path().segments().len())
+        };
+
+        match (matching(Some(status.code)), matching(None)) {
+            (Some(explicit), Some(default))
+                if default.base.path().segments().len() > explicit.base.path().segments().len() =>
+            {
+                Self::from_catcher(default)
+            }
+            (Some(catcher), _) | (None, Some(catcher)) => Self::from_catcher(catcher),
+            (None, None) => Self::rocket_default(Some(status.code)),
+        }
+    }
+}
+
+// added to the response logger of requests answered by a catcher
+pub(crate) struct CatcherFields {
+    caught: Caught,
+    status: u16,
+    failure: &'static str,
+    failed_guard: Option<String>,
+}
+
+impl KV for CatcherFields {
+    fn serialize(&self, record: &Record, serializer: &mut dyn Serializer) -> slog::Result {
+        slog::kv!(
+            "catcher" => self.caught.name.as_deref(),
+            "catcher_code" => self.caught.code,
+            "error_status" => self.status,
+            "failure" => self.failure,
+            "failed_guard" => self.failed_guard.as_deref(),
+        )
+        .serialize(record, serializer)
+    }
+}
+
+fn status_in(outcome: &str) -> Option<u16> {
+    outcome.split_once('(')?.1.get(..3)?.parse().ok()
+}
+
+// Rocket's messages for each way a route can forward
+fn forward_reason(message: &str) -> Option<String> {
+    if let Some(guard) = guard_name(message, " is forwarding") {
+        let kind = if message.starts_with("Data") {
+            "data"
+        } else {
+            "request"
+        };
+        return Some(format!("{kind} guard `{guard}`"));
+    }
+
+    if let Some(parameter) = message.strip_prefix("Parameter guard `") {
+        let (parameter, _) = parameter.split_once("` is forwarding")?;
+        return Some(format!("parameter guard `{parameter}`"));
+    }
+
+    (message == "Query string failed to match route declaration.").then(|| "query".to_string())
+}
+
+// from `Request guard `<type>` <rest>` and `Data guard `<type>` <rest>`
+fn guard_name(message: &str, rest: &str) -> Option<String> {
+    let guard = message
+        .strip_prefix("Request guard `")
+        .or_else(|| message.strip_prefix("Data guard `"))?;
+    let (guard, after) = guard.split_once('`')?;
+
+    after.starts_with(rest).then(|| guard.to_string())
+}
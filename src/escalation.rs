@@ -0,0 +1,137 @@
+use hmac::{Hmac, Mac};
+use rocket::Request;
+use sha2::Sha256;
+use slog::{FilterLevel, Level};
+use std::fmt::Write as _;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+type HmacSha256 = Hmac<Sha256>;
+
+// field added to request loggers, read back by the runtime level filter
+pub(crate) const ESCALATION_KEY: &str = "debug_escalation";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Escalation {
+    Granted(Level),
+    Rejected,
+}
+
+impl Escalation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Granted(level) => level.as_str(),
+            Self::Rejected => "REJECTED",
+        }
+    }
+
+    // escalations never make a logger quieter than it already is
+    pub(crate) fn from_field(value: &str) -> Option<FilterLevel> {
+        Level::from_str(value)
+            .ok()
+            .map(|level| FilterLevel::from_usize(level.as_usize()).unwrap_or_else(FilterLevel::max))
+    }
+}
+
+// tokens look like `<level>.<expiry as unix seconds>.<hex encoded HMAC-SHA256 of the first two parts>`
+#[derive(Clone)]
+pub struct DebugEscalation {
+    secret: Vec<u8>,
+    header: &'static str,
+    cookie: Option<&'static str>,
+}
+
+impl DebugEscalation {
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            secret: secret.into(),
+            header: "X-Debug-Log",
+            cookie: None,
+        }
+    }
+
+    pub fn header(mut self, header: &'static str) -> Self {
+        self.header = header;
+        self
+    }
+
+    pub fn header_name(&self) -> &'static str {
+        self.header
+    }
+
+    // also looks for the token in this cookie, for requests made by browsers,
+    // the header winning when both are sent
+    pub fn cookie(mut self, cookie: &'static str) -> Self {
+        self.cookie = Some(cookie);
+        self
+    }
+
+    pub fn cookie_name(&self) -> Option<&'static str> {
+        self.cookie
+    }
+
+    // `None` only when the secret can not key the HMAC, which HMAC-SHA256 allows for secrets of any length
+    pub fn token(&self, level: Level, valid_for: Duration) -> Option<String> {
+        let expires = (SystemTime::now() + valid_for)
+            .duration_since(UNIX_EPOCH)
+            .map(|expires| expires.as_secs())
+            .unwrap_or_default();
+
+        let payload = format!("{}.{}", level.as_short_str(), expires);
+        let signature = self.mac(&payload)?.finalize().into_bytes();
+
+        let mut token = payload;
+        token.push('.');
+        for byte in signature {
+            let _ = write!(token, "{byte:02x}");
+        }
+        Some(token)
+    }
+
+    // `None` when the request does not ask for an escalation at all
+    pub fn check(&self, request: &Request<'_>) -> Option<Escalation> {
+        let cookie = self.cookie.and_then(|name| request.cookies().get(name));
+        let token = request
+            .headers()
+            .get_one(self.header)
+            .or(cookie.map(|cookie| cookie.value()))?;
+
+        Some(match self.verify(token) {
+            Some(level) => Escalation::Granted(level),
+            None => Escalation::Rejected,
+        })
+    }
+
+    fn verify(&self, token: &str) -> Option<Level> {
+        let (payload, signature) = token.rsplit_once('.')?;
+        let (level, expires) = payload.split_once('.')?;
+
+        self.mac(payload)?
+            .verify_slice(&decode_hex(signature)?)
+            .ok()?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        if expires.parse::<u64>().ok()? < now {
+            return None;
+        }
+
+        Level::from_str(level).ok()
+    }
+
+    fn mac(&self, payload: &str) -> Option<HmacSha256> {
+        let mut mac = HmacSha256::new_from_slice(&self.secret).ok()?;
+        mac.update(payload.as_bytes());
+        Some(mac)
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
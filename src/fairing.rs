@@ -1,6 +1,14 @@
-use crate::{info, Slogger};
+use crate::dispatch::Dispatch;
+use crate::fields::RequestFields;
+use crate::request_ext::RequestLogger;
+use crate::{info, log_fields, Slogger};
+
+#[cfg(feature = "callbacks")]
+use crate::callbacks::Decided;
 use rocket::fairing::{Fairing, Info, Kind};
 use rocket::{Build, Config, Data, Orbit, Request, Response, Rocket};
+use slog::Logger;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 #[inline]
@@ -27,27 +35,179 @@ fn temp_dir_path_from_rocket_config(config: &Config) -> String {
         .unwrap_or_else(|_| String::from(""))
 }
 
+// how routes and catchers are logged once Rocket has launched
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RouteRecords {
+    // one record for every route and every catcher
+    #[default]
+    Each,
+    // a single record counting routes and catchers by base path
+    Summary,
+    // a single record with every route and catcher as JSON arrays
+    Table,
+    Off,
+}
+
+fn log_each_route(logger: &Logger, rocket: &Rocket<Orbit>, url: &str) {
+    for route in rocket.routes() {
+        info!(
+            logger,
+            "Route Registered";
+            "rank" => route.rank,
+            "route" => route.name.as_ref().map(|route| route.to_string()),
+            "content-type" => route.format.as_ref().map(|format| format.to_string()),
+            "path" => %route.uri,
+            "url" => format!("{}{}", url, route.uri),
+            "method" => %route.method,
+        );
+    }
+
+    for catcher in rocket.catchers() {
+        info!(
+            logger,
+            "Catcher Registered";
+            "route" => catcher.name.as_ref().map(|catcher| catcher.to_string()),
+            "code" => catcher.code,
+            "path" => %catcher.base,
+            "url" => format!("{}{}", url, catcher.base),
+        );
+    }
+}
+
+fn log_route_summary(logger: &Logger, rocket: &Rocket<Orbit>) {
+    let mut bases = BTreeMap::<&str, usize>::new();
+    for route in rocket.routes() {
+        *bases.entry(route.uri.base()).or_default() += 1;
+    }
+
+    let by_base = bases
+        .iter()
+        .map(|(base, count)| format!("{base}: {count}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    info!(
+        logger,
+        "Routes Registered";
+        "catchers" => rocket.catchers().count(),
+        "by_base" => by_base,
+        "routes" => rocket.routes().count(),
+    );
+}
+
+fn log_route_table(logger: &Logger, rocket: &Rocket<Orbit>) {
+    let routes = rocket
+        .routes()
+        .map(|route| {
+            format!(
+                r#"{{"method":{},"path":{},"route":{},"rank":{},"content-type":{}}}"#,
+                json_string(route.method.as_str()),
+                json_string(&route.uri.to_string()),
+                json_option(route.name.as_deref()),
+                route.rank,
+                json_option(
+                    route
+                        .format
+                        .as_ref()
+                        .map(|format| format.to_string())
+                        .as_deref()
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let catchers = rocket
+        .catchers()
+        .map(|catcher| {
+            format!(
+                r#"{{"code":{},"path":{},"route":{}}}"#,
+                catcher
+                    .code
+                    .map_or_else(|| "null".to_string(), |code| code.to_string()),
+                json_string(&catcher.base.to_string()),
+                json_option(catcher.name.as_deref()),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    info!(
+        logger,
+        "Routes Registered";
+        "catchers" => format!("[{}]", catchers.join(",")),
+        "routes" => format!("[{}]", routes.join(",")),
+    );
+}
+
+pub(crate) fn json_option(value: Option<&str>) -> String {
+    value.map_or_else(|| "null".to_string(), json_string)
+}
+
+pub(crate) fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
 #[rocket::async_trait]
 impl Fairing for Slogger {
     fn info(&self) -> Info {
         Info {
             name: "Slog Fairing",
-            kind: Kind::Ignite | Kind::Liftoff | Kind::Request | Kind::Response,
+            kind: Kind::Ignite | Kind::Liftoff | Kind::Request | Kind::Response | Kind::Shutdown,
         }
     }
 
     async fn on_ignite(&self, rocket: Rocket<Build>) -> Result<Rocket<Build>, Rocket<Build>> {
-        Ok(rocket.manage(self.clone()))
+        #[cfg(feature = "callbacks")]
+        for handler in &self.ignite_handlers {
+            handler(self.logger.clone(), &rocket).await;
+        }
+
+        if self.panic_hook {
+            crate::panic::install(self.logger.clone());
+        }
+
+        #[allow(unused_mut)]
+        let mut rocket = rocket.manage(self.clone());
+
+        #[cfg(feature = "runtime_level")]
+        if let (Some(handle), Some((base, auth))) = (&self.level_handle, &self.level_route) {
+            rocket = rocket
+                .manage(handle.clone())
+                .manage(auth.clone())
+                .mount(base.as_str(), crate::level::routes());
+        }
+
+        Ok(rocket)
     }
 
     async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        #[allow(unused_mut)]
+        let mut logger = self.logger.clone();
+
+        #[cfg(feature = "callbacks")]
+        for handler in &self.liftoff_handlers {
+            if let Some(new_logger) = handler(logger.clone(), rocket).await {
+                logger = new_logger;
+            }
+        }
+
         let config = rocket.config();
 
         let url = url_from_rocket_config(config);
         let temp_dir_string = temp_dir_path_from_rocket_config(config);
 
         info!(
-            &self.logger,
+            logger,
             "Rocket Launched";
             "log_level" => %config.log_level,
             "temp_dir" => temp_dir_string,
@@ -62,32 +222,20 @@ impl Fairing for Slogger {
             "profile" => %config.profile,
         );
 
-        for route in rocket.routes() {
-            info!(
-                &self.logger,
-                "Route Registered";
-                "rank" => route.rank,
-                "route" => route.name.as_ref().map(|route| route.to_string()),
-                "content-type" => route.format.as_ref().map(|format| format.to_string()),
-                "path" => %route.uri,
-                "url" => format!("{}{}", url, route.uri),
-                "method" => %route.method,
-            );
+        match self.route_records {
+            RouteRecords::Each => log_each_route(&logger, rocket, &url),
+            RouteRecords::Summary => log_route_summary(&logger, rocket),
+            RouteRecords::Table => log_route_table(&logger, rocket),
+            RouteRecords::Off => {}
         }
 
-        for catcher in rocket.catchers() {
-            info!(
-                &self.logger,
-                "Catcher Registered";
-                "route" => catcher.name.as_ref().map(|catcher| catcher.to_string()),
-                "code" => catcher.code,
-                "path" => %catcher.base,
-                "url" => format!("{}{}", url, catcher.base),
-            );
+        #[cfg(feature = "stats")]
+        if let Some(stats) = &self.stats {
+            stats.clone().spawn(logger.clone(), rocket);
         }
 
         info!(
-            &self.logger,
+            logger,
             "Accepting Connections";
             "port" => config.port,
             "host" => %config.address,
@@ -95,37 +243,183 @@ impl Fairing for Slogger {
         );
     }
 
+    async fn on_shutdown(&self, rocket: &Rocket<Orbit>) {
+        #[allow(unused_mut)]
+        let mut logger = self.logger.clone();
+
+        #[cfg(feature = "callbacks")]
+        for handler in &self.shutdown_handlers {
+            if let Some(new_logger) = handler(logger.clone(), rocket).await {
+                logger = new_logger;
+            }
+        }
+
+        info!(
+            logger,
+            "Rocket Shutdown";
+            "url" => url_from_rocket_config(rocket.config()),
+        );
+    }
+
     async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        Dispatch::begin(request);
+
+        #[cfg(feature = "stats")]
+        if let Some(stats) = &self.stats {
+            stats.request_started(request);
+        }
+
         #[allow(unused_mut)]
         let mut logger = Arc::new(self.get_for_request(request));
 
+        #[cfg(feature = "tracing")]
+        if let Some(span) = crate::tracing::RequestSpan::open(request)
+            .attach_on(request)
+            .0
+            .id()
+        {
+            Dispatch::attach_span(request, span);
+        }
+
         #[cfg(feature = "callbacks")]
-        for handler in &self.request_handlers {
-            if let Some(new_logger) = handler(logger.clone(), request).await {
-                logger = new_logger;
+        {
+            let fields = RequestFields::of(request).clone();
+            for handler in &self.request_handlers {
+                handler.fields(&fields, request).await;
+            }
+
+            if !fields.is_empty() {
+                logger = Arc::new(logger.new(log_fields!(fields.snapshot())));
+            }
+        }
+
+        #[cfg(not(feature = "callbacks"))]
+        {
+            RequestLogger::attach_on(logger.clone(), request);
+            Dispatch::attach_logger(request, logger.clone());
+
+            info!(logger, "Request");
+        }
+
+        #[cfg(feature = "callbacks")]
+        {
+            let mut decided = Decided::new(logger, "Request");
+            let mut suppressed = false;
+            for handler in &self.request_handlers {
+                if !decided.apply(handler.decide(decided.logger.clone(), request).await) {
+                    suppressed = true;
+                    break;
+                }
+            }
+
+            // cached even when suppressed, as routes still log through it
+            RequestLogger::attach_on(decided.logger.clone(), request);
+            Dispatch::attach_logger(request, decided.logger.clone());
+
+            if !suppressed {
+                decided.log(());
             }
         }
 
-        info!(logger, "Request");
+        // logged even when the request record is suppressed
+        #[cfg(feature = "security_events")]
+        if let (Some(security), Some(logger)) = (&self.security, RequestLogger::get(request)) {
+            security.inspect_request(&logger, request);
+        }
     }
 
     async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
-        #[allow(unused_mut)]
+        // counted even when the response record is suppressed
+        #[cfg(feature = "stats")]
+        if let Some(stats) = &self.stats {
+            stats.request_finished(request, response.status());
+        }
+
         let mut logger = Arc::new(self.get_for_response(request, response));
 
+        let dispatch = Dispatch::finish(request);
+        if let Some(chain) = dispatch.route_chain(request) {
+            logger = Arc::new(logger.new(log_fields!(chain)));
+        }
+        if let Some(catcher) = dispatch.catcher_fields(request, response.status()) {
+            logger = Arc::new(logger.new(log_fields!(catcher)));
+        }
+
+        #[cfg(feature = "identity")]
+        let principal = self
+            .identity
+            .as_ref()
+            .and_then(|identify| identify(request));
+
+        #[cfg(feature = "identity")]
+        if let Some(principal) = &principal {
+            logger = Arc::new(logger.new(log_fields!(principal.clone())));
+        }
+
+        #[cfg(feature = "security_events")]
+        if let Some(security) = &self.security {
+            security.inspect_response(&logger, request, response.status());
+        }
+
+        #[cfg(feature = "audit")]
+        if let Some(audit) = self
+            .audit
+            .as_ref()
+            .filter(|audit| audit.applies_to(request))
+        {
+            let audit_logger = audit.logger_for(request);
+
+            #[cfg(feature = "identity")]
+            let audit_logger = match principal {
+                Some(principal) => audit_logger.new(log_fields!(principal)),
+                None => audit_logger,
+            };
+
+            info!(
+                audit_logger,
+                "Audit";
+                "reason" => response.status().reason(),
+                "code" => response.status().code,
+            );
+        }
+
+        // includes fields from request callbacks and from routes annotating their loggers
+        let fields = RequestFields::of(request).clone();
+
         #[cfg(feature = "callbacks")]
         for handler in &self.response_handlers {
-            if let Some(new_logger) = handler(logger.clone(), request, response).await {
-                logger = new_logger;
-            }
+            handler.fields(&fields, request, response).await;
+        }
+
+        if !fields.is_empty() {
+            logger = Arc::new(logger.new(log_fields!(fields.snapshot())));
         }
 
+        #[cfg(feature = "callbacks")]
+        let decided = {
+            let mut decided = Decided::new(logger, "Response");
+            for handler in &self.response_handlers {
+                let decision = handler
+                    .decide(decided.logger.clone(), request, response)
+                    .await;
+                if !decided.apply(decision) {
+                    return;
+                }
+            }
+
+            decided
+        };
+
         let body_size = response.body_mut().size().await;
 
+        #[cfg(not(feature = "callbacks"))]
         info!(
             logger,
             "Response";
             "size" => body_size,
         );
+
+        #[cfg(feature = "callbacks")]
+        decided.log(slog::kv!("size" => body_size));
     }
 }
@@ -0,0 +1,235 @@
+use rocket::Request;
+use slog::{Key, Level, Record, RecordStatic, Serializer, Value, KV};
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    None,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Str(String),
+}
+
+impl Value for FieldValue {
+    fn serialize(&self, _: &Record, key: Key, serializer: &mut dyn Serializer) -> slog::Result {
+        match self {
+            Self::None => serializer.emit_none(key),
+            Self::Bool(value) => serializer.emit_bool(key, *value),
+            Self::I64(value) => serializer.emit_i64(key, *value),
+            Self::U64(value) => serializer.emit_u64(key, *value),
+            Self::F64(value) => serializer.emit_f64(key, *value),
+            Self::Str(value) => serializer.emit_str(key, value),
+        }
+    }
+}
+
+macro_rules! impl_from_for_field_value {
+    ($($source:ty => $variant:ident as $target:ty),* $(,)?) => {
+        $(
+            impl From<$source> for FieldValue {
+                fn from(value: $source) -> Self {
+                    Self::$variant(value as $target)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_field_value!(
+    i8 => I64 as i64,
+    i16 => I64 as i64,
+    i32 => I64 as i64,
+    i64 => I64 as i64,
+    isize => I64 as i64,
+    u8 => U64 as u64,
+    u16 => U64 as u64,
+    u32 => U64 as u64,
+    u64 => U64 as u64,
+    usize => U64 as u64,
+    f32 => F64 as f64,
+    f64 => F64 as f64,
+);
+
+impl From<bool> for FieldValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_string())
+    }
+}
+
+impl<T: Into<FieldValue>> From<Option<T>> for FieldValue {
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Self::None)
+    }
+}
+
+// key-values collected over the life of a request, shared through the request local cache
+// and added to the request and response loggers all at once
+#[derive(Clone, Debug, Default)]
+pub struct RequestFields {
+    fields: Arc<Mutex<Vec<(&'static str, FieldValue)>>>,
+}
+
+impl RequestFields {
+    pub fn of<'r>(request: &'r Request<'_>) -> &'r Self {
+        request.local_cache(Self::default)
+    }
+
+    // replaces the value of a key that was already set, keeping its original position
+    pub fn insert(&self, key: &'static str, value: impl Into<FieldValue>) {
+        let value = value.into();
+        let mut fields = self.lock();
+
+        match fields.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, existing)) => *existing = value,
+            None => fields.push((key, value)),
+        }
+    }
+
+    // adds every key-value pair, such as those made with `o!`, replacing existing keys
+    pub fn extend<T: KV>(&self, kv: &T) {
+        // serializing needs a record, though none of the collected values look at it
+        static RECORD: RecordStatic<'static> = slog::record_static!(Level::Info, "");
+
+        let mut collector = FieldCollector(self);
+        let _ = kv.serialize(
+            &Record::new(&RECORD, &format_args!(""), slog::b!()),
+            &mut collector,
+        );
+    }
+
+    pub fn get(&self, key: &str) -> Option<FieldValue> {
+        self.lock()
+            .iter()
+            .find(|(existing, _)| *existing == key)
+            .map(|(_, value)| value.clone())
+    }
+
+    pub fn remove(&self, key: &str) -> Option<FieldValue> {
+        let mut fields = self.lock();
+        let index = fields.iter().position(|(existing, _)| *existing == key)?;
+
+        Some(fields.remove(index).1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn snapshot(&self) -> FieldsSnapshot {
+        FieldsSnapshot(self.lock().clone())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<(&'static str, FieldValue)>> {
+        self.fields
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// a copy of the fields at one point in time, which can be given to `Logger::new`
+#[derive(Clone, Debug)]
+pub struct FieldsSnapshot(Vec<(&'static str, FieldValue)>);
+
+impl KV for FieldsSnapshot {
+    fn serialize(&self, record: &Record, serializer: &mut dyn Serializer) -> slog::Result {
+        for (key, value) in &self.0 {
+            value.serialize(record, Key::from(*key), serializer)?;
+        }
+        Ok(())
+    }
+}
+
+struct FieldCollector<'f>(&'f RequestFields);
+
+// every integer is kept as a number, as the ones not overridden would be formatted into strings
+macro_rules! integers {
+    ($($method:ident: $type:ty),* $(,)?) => {
+        $(
+            fn $method(&mut self, key: Key, value: $type) -> slog::Result {
+                self.0.insert(key, value);
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl Serializer for FieldCollector<'_> {
+    fn emit_arguments(&mut self, key: Key, value: &fmt::Arguments) -> slog::Result {
+        self.0.insert(key, value.to_string());
+        Ok(())
+    }
+
+    fn emit_none(&mut self, key: Key) -> slog::Result {
+        self.0.insert(key, FieldValue::None);
+        Ok(())
+    }
+
+    fn emit_bool(&mut self, key: Key, value: bool) -> slog::Result {
+        self.0.insert(key, value);
+        Ok(())
+    }
+
+    integers!(
+        emit_usize: usize,
+        emit_isize: isize,
+        emit_u8: u8,
+        emit_i8: i8,
+        emit_u16: u16,
+        emit_i16: i16,
+        emit_u32: u32,
+        emit_i32: i32,
+        emit_u64: u64,
+        emit_i64: i64,
+    );
+
+    // kept as a number when it fits in 64 bits, and written out in full otherwise
+    fn emit_u128(&mut self, key: Key, value: u128) -> slog::Result {
+        match u64::try_from(value) {
+            Ok(value) => self.0.insert(key, value),
+            Err(_) => self.0.insert(key, value.to_string()),
+        }
+        Ok(())
+    }
+
+    fn emit_i128(&mut self, key: Key, value: i128) -> slog::Result {
+        match i64::try_from(value) {
+            Ok(value) => self.0.insert(key, value),
+            Err(_) => self.0.insert(key, value.to_string()),
+        }
+        Ok(())
+    }
+
+    fn emit_f32(&mut self, key: Key, value: f32) -> slog::Result {
+        self.0.insert(key, value);
+        Ok(())
+    }
+
+    fn emit_f64(&mut self, key: Key, value: f64) -> slog::Result {
+        self.0.insert(key, value);
+        Ok(())
+    }
+
+    fn emit_str(&mut self, key: Key, value: &str) -> slog::Result {
+        self.0.insert(key, value);
+        Ok(())
+    }
+}
@@ -1,21 +1,145 @@
-use crate::Slogger;
+use crate::kv::KeyValueList;
+use crate::request_ext::RequestLogger;
+use crate::{log_fields, Slogger};
 use rocket::http::Status;
 use rocket::request::{FromRequest, Outcome};
 use rocket::{Request, State};
+use slog::{Drain, Logger, OwnedKVList, Record};
+use std::fmt;
+use std::io::Write;
+use std::sync::{Arc, Once, RwLock};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SloggerError {
+    // the `Slogger` fairing was never attached to the Rocket instance handling the request
+    FairingNotAttached,
+}
+
+impl fmt::Display for SloggerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FairingNotAttached => write!(
+                f,
+                "the Slogger fairing is not attached, add it with `.attach(Slogger::new_...())`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SloggerError {}
+
+// what the `Slogger` request guard and `RequestSloggerExt` give when the fairing is not attached,
+// such as for routes of a library mounted by an application that does not use rocket-slogger
+#[derive(Clone, Default)]
+pub enum GuardFallback {
+    // fails the request guard with `SloggerError::FairingNotAttached`, a plain 500 unless the route takes
+    // an `Option<Slogger>` or `Result<Slogger, SloggerError>`
+    #[default]
+    Fail,
+    // succeeds with a logger that drops every record
+    Discard,
+    // succeeds with the given logger
+    Logger(Logger),
+}
+
+static GUARD_FALLBACK: RwLock<GuardFallback> = RwLock::new(GuardFallback::Fail);
+
+impl GuardFallback {
+    // applies to every Rocket instance of the process, as there is no fairing to hold it
+    pub fn set(self) {
+        *GUARD_FALLBACK
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = self;
+    }
+
+    pub fn get() -> Self {
+        GUARD_FALLBACK
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    pub(crate) fn logger(&self) -> Option<Logger> {
+        match self {
+            Self::Fail => None,
+            Self::Discard => Some(Logger::root(slog::Discard, log_fields!())),
+            Self::Logger(logger) => Some(logger.clone()),
+        }
+    }
+
+    // logged once per process straight to stderr, since there is no configured logger to log through
+    pub(crate) fn report_missing_fairing(request: &Request<'_>) {
+        static REPORTED: Once = Once::new();
+
+        REPORTED.call_once(|| {
+            let logger = Logger::root(StderrDrain.ignore_res(), log_fields!());
+            slog::error!(logger, "{}", SloggerError::FairingNotAttached;
+                "uri" => request.uri().to_string(),
+                "route" => request.route().and_then(|route| route.name.as_ref()).map(|name| name.to_string()),
+                "fallback" => match Self::get() {
+                    Self::Fail => "fail",
+                    Self::Discard => "discard",
+                    Self::Logger(_) => "logger",
+                },
+            );
+        });
+    }
+}
+
+// the fallback drain used to report the fairing missing
+struct StderrDrain;
+
+impl Drain for StderrDrain {
+    type Ok = ();
+    type Err = std::io::Error;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<(), std::io::Error> {
+        let mut line = format!(
+            "rocket-slogger {} {}",
+            record.level().as_str(),
+            record.msg()
+        );
+        for (key, value) in KeyValueList::from_record(record, values).into_inner() {
+            line.push_str(&format!(", {key}: {value}"));
+        }
+
+        writeln!(std::io::stderr().lock(), "{line}")
+    }
+}
 
 #[rocket::async_trait]
 impl<'r> FromRequest<'r> for Slogger {
-    type Error = ();
+    type Error = SloggerError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Slogger, SloggerError> {
+        // built once by the fairing for each request, so any number of guards can share it
+        if let Some(logger) = RequestLogger::get_routed(request) {
+            return Outcome::Success(Slogger::from_request_logger(logger, request));
+        }
 
-    async fn from_request(request: &'r Request<'_>) -> Outcome<Slogger, ()> {
         match request.guard::<&State<Slogger>>().await {
             Outcome::Success(slogger) => {
                 let logger = slogger.get_for_request(request);
 
-                rocket::outcome::Outcome::Success(Slogger::from_logger(logger))
+                rocket::outcome::Outcome::Success(Slogger::from_request_logger(
+                    Arc::new(logger),
+                    request,
+                ))
             }
 
-            _ => Outcome::Error((Status::InternalServerError, ())),
+            _ => {
+                GuardFallback::report_missing_fairing(request);
+
+                match GuardFallback::get().logger() {
+                    Some(logger) => {
+                        Outcome::Success(Slogger::from_request_logger(Arc::new(logger), request))
+                    }
+                    None => Outcome::Error((
+                        Status::InternalServerError,
+                        SloggerError::FairingNotAttached,
+                    )),
+                }
+            }
         }
     }
 }
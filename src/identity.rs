@@ -0,0 +1,67 @@
+use rocket::Request;
+use slog::{Record, Serializer, KV};
+use std::sync::Arc;
+
+pub(crate) type Extractor = dyn Fn(&Request<'_>) -> Option<Principal> + Send + Sync + 'static;
+
+// who made a request, added to its response log
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Principal {
+    pub user_id: Option<String>,
+    pub client_id: Option<String>,
+    pub scopes: Vec<String>,
+}
+
+impl Principal {
+    pub fn user(user_id: impl ToString) -> Self {
+        Self {
+            user_id: Some(user_id.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn client(client_id: impl ToString) -> Self {
+        Self {
+            client_id: Some(client_id.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_client(mut self, client_id: impl ToString) -> Self {
+        self.client_id = Some(client_id.to_string());
+        self
+    }
+
+    pub fn with_scopes<S: ToString>(mut self, scopes: impl IntoIterator<Item = S>) -> Self {
+        self.scopes = scopes.into_iter().map(|scope| scope.to_string()).collect();
+        self
+    }
+}
+
+impl KV for Principal {
+    fn serialize(&self, record: &Record, serializer: &mut dyn Serializer) -> slog::Result {
+        let scopes = (!self.scopes.is_empty()).then(|| self.scopes.join(" "));
+
+        slog::kv!(
+            "scopes" => scopes,
+            "client_id" => self.client_id.as_deref(),
+            "user_id" => self.user_id.as_deref(),
+        )
+        .serialize(record, serializer)
+    }
+}
+
+// reads what a request guard cached as an `Option<T>`, which is what `Request::local_cache_async`
+// gives when caching the result of a fallible lookup
+pub(crate) fn from_cache<T, F>(extract: F) -> Arc<Extractor>
+where
+    T: Send + Sync + 'static,
+    F: Fn(&T) -> Principal + Send + Sync + 'static,
+{
+    Arc::new(move |request| {
+        request
+            .local_cache(|| Option::<T>::None)
+            .as_ref()
+            .map(&extract)
+    })
+}
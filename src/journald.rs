@@ -0,0 +1,102 @@
+use crate::kv::KeyValueList;
+use slog::{Drain, Level, OwnedKVList, Record};
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+
+pub struct JournaldDrain {
+    socket: UnixDatagram,
+    path: PathBuf,
+    identifier: String,
+}
+
+impl JournaldDrain {
+    pub fn new(identifier: &str) -> io::Result<Self> {
+        Self::with_socket(identifier, JOURNAL_SOCKET)
+    }
+
+    // mostly useful to point the drain at a local listener instead of journald itself
+    pub fn with_socket(identifier: &str, path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            socket: UnixDatagram::unbound()?,
+            path: path.as_ref().to_path_buf(),
+            identifier: identifier.to_string(),
+        })
+    }
+
+    fn format(&self, record: &Record, values: &OwnedKVList) -> Vec<u8> {
+        let mut payload = Vec::new();
+
+        append_field(&mut payload, "MESSAGE", &record.msg().to_string());
+        append_field(&mut payload, "PRIORITY", priority(record.level()));
+        append_field(&mut payload, "SYSLOG_IDENTIFIER", &self.identifier);
+        append_field(&mut payload, "CODE_FILE", record.file());
+        append_field(&mut payload, "CODE_LINE", &record.line().to_string());
+        append_field(&mut payload, "CODE_MODULE", record.module());
+
+        for (key, value) in KeyValueList::from_record(record, values).into_inner() {
+            append_field(&mut payload, &field_name(&key), &value);
+        }
+
+        payload
+    }
+}
+
+impl Drain for JournaldDrain {
+    type Ok = ();
+    type Err = io::Error;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> io::Result<()> {
+        // payloads too large for a single datagram would need to be passed as a sealed memfd, which is not
+        // done here, so those records are lost and reported back as errors from the socket
+        self.socket
+            .send_to(&self.format(record, values), &self.path)
+            .map(|_| ())
+    }
+}
+
+fn priority(level: Level) -> &'static str {
+    match level {
+        Level::Critical => "2",
+        Level::Error => "3",
+        Level::Warning => "4",
+        Level::Info => "6",
+        Level::Debug | Level::Trace => "7",
+    }
+}
+
+// values containing newlines use the length-prefixed binary form of the native protocol
+fn append_field(payload: &mut Vec<u8>, name: &str, value: &str) {
+    payload.extend_from_slice(name.as_bytes());
+
+    if value.contains('\n') {
+        payload.push(b'\n');
+        payload.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        payload.push(b'=');
+    }
+
+    payload.extend_from_slice(value.as_bytes());
+    payload.push(b'\n');
+}
+
+// journal field names are upper case letters, digits and underscores, not starting
+// with an underscore or digit (leading underscores are reserved for trusted fields)
+fn field_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| match c {
+            c if c.is_ascii_alphanumeric() => c.to_ascii_uppercase(),
+            _ => '_',
+        })
+        .collect();
+    let name = name.trim_start_matches(|c: char| c == '_' || c.is_ascii_digit());
+
+    if name.is_empty() {
+        String::from("FIELD")
+    } else {
+        name.chars().take(64).collect()
+    }
+}
@@ -0,0 +1,79 @@
+use slog::{Key, OwnedKVList, Record, Serializer, KV};
+use std::fmt;
+
+// flattens every key-value pair of a record, including those of its logger,
+// into owned strings for drains that format their own output
+#[derive(Default)]
+pub(crate) struct KeyValueList(pub Vec<(String, String)>);
+
+impl KeyValueList {
+    pub fn from_record(record: &Record, values: &OwnedKVList) -> Self {
+        let mut list = Self::default();
+
+        // errors only come from the serializer itself, which never fails
+        let _ = record.kv().serialize(record, &mut list);
+        let _ = values.serialize(record, &mut list);
+
+        list
+    }
+
+    pub fn into_inner(self) -> Vec<(String, String)> {
+        self.0
+    }
+}
+
+impl Serializer for KeyValueList {
+    fn emit_arguments(&mut self, key: Key, value: &fmt::Arguments) -> slog::Result {
+        self.0.push((key.to_string(), value.to_string()));
+        Ok(())
+    }
+}
+
+// looks up the first value logged under any of the given keys without collecting the rest
+pub(crate) struct KeyFinder<'k> {
+    keys: &'k [&'static str],
+    found: Option<String>,
+}
+
+impl<'k> KeyFinder<'k> {
+    pub fn find(record: &Record, values: &OwnedKVList, keys: &'k [&'static str]) -> Option<String> {
+        let mut finder = Self { keys, found: None };
+
+        let _ = record.kv().serialize(record, &mut finder);
+        if finder.found.is_none() {
+            let _ = values.serialize(record, &mut finder);
+        }
+
+        finder.found
+    }
+}
+
+impl Serializer for KeyFinder<'_> {
+    fn emit_arguments(&mut self, key: Key, value: &fmt::Arguments) -> slog::Result {
+        if self.found.is_none() && self.keys.contains(&key) {
+            self.found = Some(value.to_string());
+        }
+        Ok(())
+    }
+}
+
+// Rocket colors parts of its messages when it thinks it is writing to a terminal
+pub(crate) fn strip_ansi_codes(message: &str) -> String {
+    let mut stripped = String::with_capacity(message.len());
+    let mut chars = message.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // skip the CSI sequence up to and including its final letter
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
@@ -0,0 +1,281 @@
+#[cfg(feature = "debug_escalation")]
+use crate::escalation::{Escalation, ESCALATION_KEY};
+use crate::kv::KeyFinder;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::response::status::BadRequest;
+use rocket::{get, put, Request, Route, State};
+use slog::{Drain, FilterLevel, OwnedKVList, Record};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+#[derive(Clone, Debug)]
+struct LevelConfig {
+    global: FilterLevel,
+    modules: BTreeMap<String, FilterLevel>,
+    routes: BTreeMap<String, FilterLevel>,
+}
+
+// cheap to clone, every clone controls the same filter
+#[derive(Clone, Debug)]
+pub struct LevelHandle {
+    config: Arc<RwLock<LevelConfig>>,
+}
+
+impl LevelHandle {
+    pub fn new(level: FilterLevel) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(LevelConfig {
+                global: level,
+                modules: BTreeMap::new(),
+                routes: BTreeMap::new(),
+            })),
+        }
+    }
+
+    pub fn level(&self) -> FilterLevel {
+        self.read(|config| config.global)
+    }
+
+    pub fn set_level(&self, level: FilterLevel) {
+        self.write(|config| config.global = level);
+    }
+
+    // applies to records logged from the module or any of its sub-modules,
+    // the longest matching module path wins, `None` removes the override
+    pub fn set_module_level(&self, module: &str, level: Option<FilterLevel>) {
+        self.write(|config| match level {
+            Some(level) => config.modules.insert(module.to_string(), level),
+            None => config.modules.remove(module),
+        });
+    }
+
+    // applies to loggers carrying a matching `route` name or `path` field,
+    // such as the request and response loggers, `None` removes the override
+    pub fn set_route_level(&self, route: &str, level: Option<FilterLevel>) {
+        self.write(|config| match level {
+            Some(level) => config.routes.insert(route.to_string(), level),
+            None => config.routes.remove(route),
+        });
+    }
+
+    pub fn module_levels(&self) -> BTreeMap<String, FilterLevel> {
+        self.read(|config| config.modules.clone())
+    }
+
+    pub fn route_levels(&self) -> BTreeMap<String, FilterLevel> {
+        self.read(|config| config.routes.clone())
+    }
+
+    pub fn accepts(&self, record: &Record, values: &OwnedKVList) -> bool {
+        self.effective_level(record, values).accepts(record.level())
+    }
+
+    fn effective_level(&self, record: &Record, values: &OwnedKVList) -> FilterLevel {
+        let level = self.configured_level(record, values);
+
+        #[cfg(feature = "debug_escalation")]
+        if let Some(escalated) = KeyFinder::find(record, values, &[ESCALATION_KEY])
+            .and_then(|escalation| Escalation::from_field(&escalation))
+        {
+            if escalated.as_usize() > level.as_usize() {
+                return escalated;
+            }
+        }
+
+        level
+    }
+
+    fn configured_level(&self, record: &Record, values: &OwnedKVList) -> FilterLevel {
+        self.read(|config| {
+            if !config.routes.is_empty() {
+                let route = KeyFinder::find(record, values, &["route"])
+                    .and_then(|route| config.routes.get(&route))
+                    .or_else(|| {
+                        KeyFinder::find(record, values, &["path"])
+                            .and_then(|path| config.routes.get(&path))
+                    });
+
+                if let Some(level) = route {
+                    return *level;
+                }
+            }
+
+            config
+                .modules
+                .iter()
+                .filter(|(module, _)| is_module_or_child(record.module(), module))
+                .max_by_key(|(module, _)| module.len())
+                .map(|(_, level)| *level)
+                .unwrap_or(config.global)
+        })
+    }
+
+    fn read<T>(&self, reader: impl FnOnce(&LevelConfig) -> T) -> T {
+        match self.config.read() {
+            Ok(config) => reader(&config),
+            Err(poisoned) => reader(&poisoned.into_inner()),
+        }
+    }
+
+    fn write<T>(&self, writer: impl FnOnce(&mut LevelConfig) -> T) -> T {
+        match self.config.write() {
+            Ok(mut config) => writer(&mut config),
+            Err(poisoned) => writer(&mut poisoned.into_inner()),
+        }
+    }
+
+    fn describe(&self) -> String {
+        let config = self.read(LevelConfig::clone);
+
+        let mut description = format!("level={}\n", config.global.as_str());
+        for (module, level) in &config.modules {
+            let _ = writeln!(description, "module:{}={}", module, level.as_str());
+        }
+        for (route, level) in &config.routes {
+            let _ = writeln!(description, "route:{}={}", route, level.as_str());
+        }
+
+        description
+    }
+}
+
+fn is_module_or_child(module: &str, parent: &str) -> bool {
+    module == parent
+        || module
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with("::"))
+}
+
+pub struct ReloadableLevel<D: Drain> {
+    drain: D,
+    handle: LevelHandle,
+}
+
+impl<D: Drain> ReloadableLevel<D> {
+    pub fn new(drain: D, handle: LevelHandle) -> Self {
+        Self { drain, handle }
+    }
+
+    pub fn handle(&self) -> &LevelHandle {
+        &self.handle
+    }
+}
+
+impl<D: Drain> Drain for ReloadableLevel<D> {
+    type Ok = Option<D::Ok>;
+    type Err = D::Err;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        if self.handle.accepts(record, values) {
+            self.drain.log(record, values).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+type AuthCheck = dyn Fn(&Request<'_>) -> bool + Send + Sync + 'static;
+
+// who may use the level routes, anyone else gets a 401
+#[derive(Clone)]
+pub struct LevelRouteAuth(Arc<AuthCheck>);
+
+impl LevelRouteAuth {
+    // requests with an `Authorization: Bearer <token>` header carrying the token, never an empty one
+    pub fn bearer(token: impl Into<String>) -> Self {
+        let token = token.into();
+
+        Self::new(move |request| {
+            request
+                .headers()
+                .get_one("Authorization")
+                .and_then(|header| header.strip_prefix("Bearer "))
+                .is_some_and(|given| !token.is_empty() && constant_time_eq(given, &token))
+        })
+    }
+
+    // any check of the request, such as one of an API key header or of the client's ip
+    pub fn new<F>(check: F) -> Self
+    where
+        F: Fn(&Request<'_>) -> bool + Send + Sync + 'static,
+    {
+        Self(Arc::new(check))
+    }
+}
+
+impl fmt::Debug for LevelRouteAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LevelRouteAuth")
+    }
+}
+
+// doesn't stop at the first differing byte, so the time taken says nothing about how much of a token matched
+fn constant_time_eq(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+struct LevelAdmin;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for LevelAdmin {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, ()> {
+        match request.rocket().state::<LevelRouteAuth>() {
+            Some(LevelRouteAuth(check)) if check(request) => Outcome::Success(LevelAdmin),
+            _ => Outcome::Error((Status::Unauthorized, ())),
+        }
+    }
+}
+
+// `none` clears an override, anything else must be a level name such as `debug` or `off`
+fn parse_override(level: &str) -> Result<Option<FilterLevel>, BadRequest<String>> {
+    if level.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+
+    FilterLevel::from_str(level)
+        .map(Some)
+        .map_err(|_| BadRequest(format!("Unknown log level `{level}`\n")))
+}
+
+#[get("/")]
+fn get_level(_admin: LevelAdmin, handle: &State<LevelHandle>) -> String {
+    handle.describe()
+}
+
+#[put("/?<level>&<module>&<route>")]
+fn set_level(
+    _admin: LevelAdmin,
+    handle: &State<LevelHandle>,
+    level: &str,
+    module: Option<&str>,
+    route: Option<&str>,
+) -> Result<String, BadRequest<String>> {
+    let level = parse_override(level)?;
+
+    match (module, route, level) {
+        (Some(module), _, level) => handle.set_module_level(module, level),
+        (None, Some(route), level) => handle.set_route_level(route, level),
+        (None, None, Some(level)) => handle.set_level(level),
+        (None, None, None) => {
+            return Err(BadRequest(String::from(
+                "The global level cannot be removed\n",
+            )))
+        }
+    }
+
+    Ok(handle.describe())
+}
+
+pub(crate) fn routes() -> Vec<Route> {
+    rocket::routes![get_level, set_level]
+}
@@ -1,19 +1,81 @@
 pub mod fairing;
+pub mod fields;
 pub mod from_request;
+pub mod request_ext;
+
+#[cfg(feature = "callbacks")]
+pub mod callbacks;
 
 #[cfg(feature = "transactions")]
 pub mod transaction;
 
+#[cfg(feature = "terminal")]
+pub mod terminal;
+
+#[cfg(feature = "syslog")]
+pub mod syslog;
+
+#[cfg(all(feature = "journald", unix))]
+pub mod journald;
+
+#[cfg(feature = "runtime_level")]
+pub mod level;
+
+#[cfg(feature = "debug_escalation")]
+pub mod escalation;
+
+#[cfg(feature = "log_bridge")]
+pub mod log_bridge;
+
+#[cfg(feature = "tracing")]
+pub mod tracing;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "redaction")]
+pub mod redaction;
+
+#[cfg(feature = "url_fields")]
+mod url;
+
+#[cfg(feature = "tenants")]
+pub mod tenant;
+
+#[cfg(feature = "identity")]
+pub mod identity;
+
+#[cfg(feature = "audit")]
+pub mod audit;
+
+#[cfg(feature = "security_events")]
+pub mod security;
+
+#[cfg(feature = "stats")]
+mod stats;
+
+mod dispatch;
+#[allow(dead_code)] // not every feature uses every helper
+mod kv;
+mod panic;
+
+pub use from_request::{GuardFallback, SloggerError};
+pub use request_ext::RequestSloggerExt;
+
 // various slog re-exports for convenience
-pub use slog::{o, o as log_fields, Drain, Logger};
+pub use slog::{o, o as log_fields, Drain, FilterLevel, Level, Logger};
 // logging macros that are compiled away in release mode
 pub use slog::{debug, trace};
 // logging macros that are kept in all builds
 pub use slog::{error, info, warn};
 
-use rocket::{Request, Response};
+use rocket::{Request, Response, Route};
 use std::sync::Arc;
 
+#[allow(unused_imports)]
+use rocket::futures::future::BoxFuture;
+#[allow(unused_imports)]
+use rocket::{Build, Orbit, Rocket};
 #[allow(unused_imports)]
 use std::future::Future;
 #[allow(unused_imports)]
@@ -23,34 +85,58 @@ use std::pin::Pin;
 pub struct Slogger {
     logger: Arc<Logger>,
 
+    // only set on the loggers given to routes as request guards
+    fields: Option<fields::RequestFields>,
+
+    #[cfg(feature = "runtime_level")]
+    level_handle: Option<level::LevelHandle>,
+
+    #[cfg(feature = "runtime_level")]
+    level_route: Option<(String, level::LevelRouteAuth)>,
+
+    #[cfg(feature = "debug_escalation")]
+    escalation: Option<Arc<escalation::DebugEscalation>>,
+
+    #[cfg(feature = "callbacks")]
+    request_handlers: Vec<Arc<dyn callbacks::RequestEnricher>>,
+
     #[cfg(feature = "callbacks")]
-    request_handlers: Vec<
-        Arc<
-            dyn for<'r> Fn(
-                    Arc<Logger>,
-                    &'r mut Request<'_>,
-                )
-                    -> Pin<Box<dyn Future<Output = Option<Arc<Logger>>> + Send + 'r>>
-                + Send
-                + Sync
-                + 'static,
-        >,
-    >,
-
-    #[cfg(feature = "callbacks")]
-    response_handlers: Vec<
-        Arc<
-            dyn for<'r> Fn(
-                    Arc<Logger>,
-                    &'r Request<'_>,
-                    &'r mut Response<'_>,
-                )
-                    -> Pin<Box<dyn Future<Output = Option<Arc<Logger>>> + Send + 'r>>
-                + Send
-                + Sync
-                + 'static,
-        >,
-    >,
+    response_handlers: Vec<Arc<dyn callbacks::ResponseEnricher>>,
+
+    #[cfg(feature = "callbacks")]
+    ignite_handlers: Vec<Arc<callbacks::IgniteHandler>>,
+
+    #[cfg(feature = "callbacks")]
+    liftoff_handlers: Vec<Arc<callbacks::OrbitHandler>>,
+
+    #[cfg(feature = "callbacks")]
+    shutdown_handlers: Vec<Arc<callbacks::OrbitHandler>>,
+
+    route_records: fairing::RouteRecords,
+
+    panic_hook: bool,
+
+    #[cfg(feature = "tenants")]
+    tenants: Option<Arc<tenant::Tenants>>,
+
+    #[cfg(feature = "redaction")]
+    redaction: Option<Arc<redaction::Redaction>>,
+
+    #[cfg(feature = "identity")]
+    identity: Option<Arc<identity::Extractor>>,
+
+    #[cfg(feature = "audit")]
+    audit: Option<Arc<audit::Audit>>,
+
+    // only set on the loggers given to routes as request guards
+    #[cfg(feature = "audit")]
+    audit_logger: Option<Logger>,
+
+    #[cfg(feature = "security_events")]
+    security: Option<Arc<security::SecurityRules>>,
+
+    #[cfg(feature = "stats")]
+    stats: Option<Arc<stats::Stats>>,
 }
 
 impl Slogger {
@@ -76,6 +162,38 @@ impl Slogger {
         Self::from_logger(logger)
     }
 
+    #[cfg(all(feature = "terminal", not(feature = "envlogger")))]
+    pub fn new_pretty_terminal_logger() -> Self {
+        use std::io::IsTerminal;
+
+        // colors and compact lines are only useful to a person watching the output
+        if !std::io::stdout().is_terminal() {
+            return Self::new_terminal_logger();
+        }
+
+        let pretty_logger = terminal::PrettyDrain::new();
+        let logger = Logger::root(pretty_logger.fuse(), log_fields!());
+
+        Self::from_logger(logger)
+    }
+
+    #[cfg(all(feature = "terminal", feature = "envlogger"))]
+    pub fn new_pretty_terminal_logger() -> Self {
+        use slog_envlogger::EnvLogger;
+        use std::io::IsTerminal;
+
+        // colors and compact lines are only useful to a person watching the output
+        if !std::io::stdout().is_terminal() {
+            return Self::new_terminal_logger();
+        }
+
+        let pretty_logger = terminal::PrettyDrain::new();
+        let env_logger = EnvLogger::new(pretty_logger.fuse());
+        let logger = Logger::root(env_logger.fuse(), log_fields!());
+
+        Self::from_logger(logger)
+    }
+
     #[cfg(all(feature = "bunyan", not(feature = "envlogger")))]
     pub fn new_bunyan_logger(name: &'static str) -> Self {
         use std::sync::Mutex;
@@ -98,15 +216,115 @@ impl Slogger {
         Self::from_logger(logger)
     }
 
+    #[cfg(all(feature = "syslog", not(feature = "envlogger")))]
+    pub fn new_syslog_logger(
+        app_name: &str,
+        transport: syslog::Transport,
+    ) -> std::io::Result<Self> {
+        let syslog_logger = syslog::SyslogDrain::new(app_name, transport)?;
+        // records that could not be sent are dropped, as fusing would panic the code logging them
+        let logger = Logger::root(syslog_logger.ignore_res(), log_fields!());
+
+        Ok(Self::from_logger(logger))
+    }
+
+    #[cfg(all(feature = "syslog", feature = "envlogger"))]
+    pub fn new_syslog_logger(
+        app_name: &str,
+        transport: syslog::Transport,
+    ) -> std::io::Result<Self> {
+        use slog_envlogger::EnvLogger;
+
+        let syslog_logger = syslog::SyslogDrain::new(app_name, transport)?;
+        let env_logger = EnvLogger::new(syslog_logger.ignore_res());
+        let logger = Logger::root(env_logger.fuse(), log_fields!());
+
+        Ok(Self::from_logger(logger))
+    }
+
+    #[cfg(all(feature = "journald", unix, not(feature = "envlogger")))]
+    pub fn new_journald_logger(identifier: &str) -> std::io::Result<Self> {
+        let journald_logger = journald::JournaldDrain::new(identifier)?;
+        // records that could not be sent are dropped, as fusing would panic the code logging them
+        let logger = Logger::root(journald_logger.ignore_res(), log_fields!());
+
+        Ok(Self::from_logger(logger))
+    }
+
+    #[cfg(all(feature = "journald", unix, feature = "envlogger"))]
+    pub fn new_journald_logger(identifier: &str) -> std::io::Result<Self> {
+        use slog_envlogger::EnvLogger;
+
+        let journald_logger = journald::JournaldDrain::new(identifier)?;
+        let env_logger = EnvLogger::new(journald_logger.ignore_res());
+        let logger = Logger::root(env_logger.fuse(), log_fields!());
+
+        Ok(Self::from_logger(logger))
+    }
+
+    // every record is kept in memory with all of its key-values, for tests to assert on
+    #[cfg(feature = "testing")]
+    pub fn new_capturing() -> (Self, testing::Captured) {
+        let captured = testing::Captured::default();
+        let logger = Logger::root(testing::CapturingDrain::new(&captured), log_fields!());
+
+        (Self::from_logger(logger), captured)
+    }
+
     pub fn from_logger(logger: Logger) -> Self {
         Self {
             logger: Arc::new(logger),
 
+            fields: None,
+
+            #[cfg(feature = "runtime_level")]
+            level_handle: None,
+
+            #[cfg(feature = "runtime_level")]
+            level_route: None,
+
+            #[cfg(feature = "debug_escalation")]
+            escalation: None,
+
             #[cfg(feature = "callbacks")]
             request_handlers: vec![],
 
             #[cfg(feature = "callbacks")]
             response_handlers: vec![],
+
+            #[cfg(feature = "callbacks")]
+            ignite_handlers: vec![],
+
+            #[cfg(feature = "callbacks")]
+            liftoff_handlers: vec![],
+
+            #[cfg(feature = "callbacks")]
+            shutdown_handlers: vec![],
+
+            route_records: fairing::RouteRecords::default(),
+
+            panic_hook: false,
+
+            #[cfg(feature = "tenants")]
+            tenants: None,
+
+            #[cfg(feature = "redaction")]
+            redaction: None,
+
+            #[cfg(feature = "identity")]
+            identity: None,
+
+            #[cfg(feature = "audit")]
+            audit: None,
+
+            #[cfg(feature = "audit")]
+            audit_logger: None,
+
+            #[cfg(feature = "security_events")]
+            security: None,
+
+            #[cfg(feature = "stats")]
+            stats: None,
         }
     }
 
@@ -114,6 +332,14 @@ impl Slogger {
         &self.logger
     }
 
+    // the fields are kept with the request and added to its response log, so that details only known
+    // to the route (such as an authenticated user) show up there; the guard's own logger is unchanged
+    pub fn annotate<T: slog::SendSyncRefUnwindSafeKV>(&self, fields: slog::OwnedKV<T>) {
+        if let Some(request_fields) = &self.fields {
+            request_fields.extend(&fields.0);
+        }
+    }
+
     pub fn get_for_request(&self, request: &Request<'_>) -> Logger {
         let content_type = request.content_type().map(|format| format.to_string());
         let user_agent = request
@@ -122,25 +348,11 @@ impl Slogger {
             .collect::<Vec<_>>()
             .join("; ");
 
-        #[cfg(not(feature = "transactions"))]
-        let logger = self.logger.new(log_fields!(
+        let logger = self.get_base_for_request(request).new(log_fields!(
             "user-agent" => user_agent,
             "content-type" => content_type,
         ));
 
-        #[cfg(feature = "transactions")]
-        let logger = {
-            let transaction = transaction::RequestTransaction::new().attach_on(request);
-
-            self.logger.new(log_fields!(
-                "received" => transaction.received_as_string(),
-                "transaction" => transaction.id_as_string(),
-
-                "user-agent" => user_agent,
-                "content-type" => content_type,
-            ))
-        };
-
         Self::new_logger_with_request_details(&logger, request)
     }
 
@@ -149,7 +361,7 @@ impl Slogger {
         let status = response.status();
 
         #[cfg(not(feature = "transactions"))]
-        let logger = self.logger.new(log_fields!(
+        let logger = self.get_base_for_request(request).new(log_fields!(
             "content-type" => content_type,
             "reason" => status.reason().map(|reason| reason.to_string()),
             "code" => status.code,
@@ -159,10 +371,8 @@ impl Slogger {
         let logger = {
             let transaction = transaction::RequestTransaction::new().attach_on(request);
 
-            self.logger.new(log_fields!(
+            self.get_base_for_request(request).new(log_fields!(
                 "elapsed_ns" => transaction.elapsed_ns(),
-                "received" => transaction.received_as_string(),
-                "transaction" => transaction.id_as_string(),
                 "content-type" => content_type,
                 "reason" => status.reason().map(|reason| reason.to_string()),
                 "code" => status.code,
@@ -172,8 +382,88 @@ impl Slogger {
         Self::new_logger_with_request_details(&logger, request)
     }
 
+    // the parts shared by every logger of a request, built once and kept in the request local cache
+    fn get_base_for_request(&self, request: &Request<'_>) -> Arc<Logger> {
+        struct RequestBase(Arc<Logger>);
+
+        request
+            .local_cache(|| RequestBase(Arc::new(self.new_base_for_request(request))))
+            .0
+            .clone()
+    }
+
+    fn new_base_for_request(&self, request: &Request<'_>) -> Logger {
+        #[cfg(not(feature = "tenants"))]
+        let root = self.logger.as_ref();
+
+        // the tenant's own logger when it has one
+        #[cfg(feature = "tenants")]
+        let root = &match &self.tenants {
+            Some(tenants) => tenants.logger_for(request, &self.logger),
+            None => Logger::clone(&self.logger),
+        };
+
+        #[cfg(not(feature = "transactions"))]
+        let logger = Logger::clone(root);
+
+        #[cfg(feature = "transactions")]
+        let logger = {
+            let transaction = transaction::RequestTransaction::new().attach_on(request);
+
+            root.new(log_fields!(
+                "received" => transaction.received_as_string(),
+                "transaction" => transaction.id_as_string(),
+            ))
+        };
+
+        // recorded on the request logger whether granted or not so that escalations can be audited
+        #[cfg(feature = "debug_escalation")]
+        let logger = match self.escalation.as_ref().and_then(|e| e.check(request)) {
+            Some(escalation) => logger.new(log_fields!(
+                escalation::ESCALATION_KEY => escalation.as_str(),
+            )),
+            None => logger,
+        };
+
+        #[cfg(not(any(
+            feature = "transactions",
+            feature = "debug_escalation",
+            feature = "tenants"
+        )))]
+        let _ = request;
+
+        logger
+    }
+
+    // for loggers that already have the request's method and uri
+    fn new_logger_with_route_details(
+        logger: &Logger,
+        request: &Request<'_>,
+        route: &Route,
+    ) -> Logger {
+        let logger = logger.new(log_fields!(
+            "rank" => route.rank,
+            "route" => route.name.as_ref().map(|route| route.to_string()),
+            "path" => format!("{}", route.uri),
+        ));
+
+        #[cfg(feature = "url_fields")]
+        let logger = logger.new(log_fields!(url::PathParams::of(route, request)));
+
+        #[cfg(not(feature = "url_fields"))]
+        let _ = request;
+
+        logger
+    }
+
     fn new_logger_with_request_details(logger: &Logger, request: &Request<'_>) -> Logger {
+        #[cfg(feature = "url_fields")]
+        let logger = &logger.new(log_fields!(url::UrlFields::of(request)));
+
         if let Some(route) = request.route() {
+            #[cfg(feature = "url_fields")]
+            let logger = &logger.new(log_fields!(url::PathParams::of(route, request)));
+
             logger.new(log_fields!(
                 "rank" => route.rank,
                 "route" => route.name.as_ref().map(|route| route.to_string()),
@@ -189,9 +479,223 @@ impl Slogger {
         }
     }
 
+    #[cfg(feature = "runtime_level")]
+    pub fn with_runtime_level(mut self, level: FilterLevel) -> Self {
+        if let Some(handle) = &self.level_handle {
+            handle.set_level(level);
+            return self;
+        }
+
+        let handle = level::LevelHandle::new(level);
+        self.logger = Arc::new(Self::reloadable(self.logger.as_ref().clone(), &handle));
+
+        #[cfg(feature = "tenants")]
+        self.wrap_tenant_loggers(|logger| Self::reloadable(logger, &handle));
+
+        self.level_handle = Some(handle);
+        self
+    }
+
+    #[cfg(feature = "runtime_level")]
+    fn reloadable(logger: Logger, handle: &level::LevelHandle) -> Logger {
+        let drain = level::ReloadableLevel::new(logger, handle.clone());
+        Logger::root(drain.fuse(), log_fields!())
+    }
+
+    // wraps the logger as it is, so it should come before anything else handed the logger, such as the
+    // log bridge, for their records to be redacted too
+    #[cfg(feature = "redaction")]
+    pub fn with_redaction(mut self, redaction: redaction::Redaction) -> Self {
+        let redaction = Arc::new(redaction);
+        self.logger = Arc::new(Self::redacted(self.logger.as_ref().clone(), &redaction));
+
+        #[cfg(feature = "tenants")]
+        self.wrap_tenant_loggers(|logger| Self::redacted(logger, &redaction));
+
+        #[cfg(feature = "audit")]
+        if let Some(audit) = &mut self.audit {
+            Arc::make_mut(audit).wrap_logger(|logger| Self::redacted(logger, &redaction));
+        }
+
+        self.redaction = Some(redaction);
+        self
+    }
+
+    #[cfg(feature = "redaction")]
+    fn redacted(logger: Logger, redaction: &Arc<redaction::Redaction>) -> Logger {
+        let drain = redaction::RedactingDrain::shared(logger, redaction.clone());
+        Logger::root(drain.fuse(), log_fields!())
+    }
+
+    #[cfg(feature = "runtime_level")]
+    pub fn level_handle(&self) -> Option<&level::LevelHandle> {
+        self.level_handle.as_ref()
+    }
+
+    // mounts routes to read (`GET`) and change (`PUT ?level=&module=&route=`) levels under `base`,
+    // answering 401 to requests the auth does not let through
+    #[cfg(feature = "runtime_level")]
+    pub fn mount_level_route(mut self, base: &str, auth: level::LevelRouteAuth) -> Self {
+        if self.level_handle.is_none() {
+            self = self.with_runtime_level(FilterLevel::max());
+        }
+
+        self.level_route = Some((base.to_string(), auth));
+        self
+    }
+
+    // requests with a valid token in the escalation header get loggers at the token's level,
+    // the normal level defaults to info unless one was already set with `with_runtime_level`
+    #[cfg(feature = "debug_escalation")]
+    pub fn with_debug_escalation(mut self, escalation: escalation::DebugEscalation) -> Self {
+        if self.level_handle.is_none() {
+            self = self.with_runtime_level(FilterLevel::Info);
+        }
+
+        self.escalation = Some(Arc::new(escalation));
+        self
+    }
+
+    pub fn route_records(mut self, route_records: fairing::RouteRecords) -> Self {
+        self.route_records = route_records;
+        self
+    }
+
+    #[cfg(feature = "tenants")]
+    pub fn with_tenants(mut self, tenants: tenant::Tenants) -> Self {
+        self.tenants = Some(Arc::new(tenants));
+
+        #[cfg(feature = "redaction")]
+        if let Some(redaction) = self.redaction.clone() {
+            self.wrap_tenant_loggers(|logger| Self::redacted(logger, &redaction));
+        }
+
+        #[cfg(feature = "runtime_level")]
+        if let Some(handle) = self.level_handle.clone() {
+            self.wrap_tenant_loggers(|logger| Self::reloadable(logger, &handle));
+        }
+
+        self
+    }
+
+    // tenants with a logger of their own get the same redaction and runtime level as the fairing's logger,
+    // whichever was set first
+    #[cfg(all(
+        feature = "tenants",
+        any(feature = "redaction", feature = "runtime_level")
+    ))]
+    fn wrap_tenant_loggers(&mut self, wrap: impl Fn(Logger) -> Logger) {
+        if let Some(tenants) = &mut self.tenants {
+            Arc::make_mut(tenants).map_loggers(wrap);
+        }
+    }
+
+    // run as the response is logged, once every request guard has had the chance to authenticate the request
+    #[cfg(feature = "identity")]
+    pub fn with_identity<F>(mut self, identify: F) -> Self
+    where
+        F: Fn(&Request<'_>) -> Option<identity::Principal> + Send + Sync + 'static,
+    {
+        self.identity = Some(Arc::new(identify));
+        self
+    }
+
+    // for request guards caching an `Option<T>`, such as with `Request::local_cache_async`
+    #[cfg(feature = "identity")]
+    pub fn with_cached_identity<T, F>(mut self, identify: F) -> Self
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&T) -> identity::Principal + Send + Sync + 'static,
+    {
+        self.identity = Some(identity::from_cache(identify));
+        self
+    }
+
+    #[cfg(feature = "audit")]
+    pub fn with_audit(mut self, audit: audit::Audit) -> Self {
+        #[allow(unused_mut)]
+        let mut audit = audit;
+
+        // audit records are redacted before they are hash chained, whichever was set first
+        #[cfg(feature = "redaction")]
+        if let Some(redaction) = &self.redaction {
+            audit.wrap_logger(|logger| Self::redacted(logger, redaction));
+        }
+
+        self.audit = Some(Arc::new(audit));
+        self
+    }
+
+    // the audit channel of the request, which drops every record when the fairing has none
+    #[cfg(feature = "audit")]
+    pub fn audit(&self) -> Logger {
+        self.audit_logger
+            .clone()
+            .unwrap_or_else(|| Logger::root(slog::Discard, log_fields!()))
+    }
+
+    #[cfg(feature = "security_events")]
+    pub fn with_security_events(mut self, rules: security::SecurityRules) -> Self {
+        self.security = Some(Arc::new(rules));
+        self
+    }
+
+    // a `Request Statistics` record every interval, summarizing the requests responded to since the last
+    #[cfg(feature = "stats")]
+    pub fn with_stats(mut self, interval: std::time::Duration) -> Self {
+        self.stats = Some(Arc::new(stats::Stats::new(interval)));
+        self
+    }
+
+    // installed on ignite, panics are still passed on to the hook that was installed before
+    pub fn with_panic_hook(mut self) -> Self {
+        self.panic_hook = true;
+        self
+    }
+
+    // installs the logger as the `log` crate backend right away, so this must be called before
+    // `rocket::build()` or `rocket::custom()` get a chance to install Rocket's own logger
+    #[cfg(feature = "log_bridge")]
+    pub fn with_log_bridge(self, level: log_bridge::LevelFilter) -> Self {
+        let bridge = log_bridge::LogBridge::new(self.logger.as_ref().clone());
+
+        if let Err(error) = bridge.install(level) {
+            warn!(
+                &self.logger,
+                "Log Bridge Not Installed";
+                "error" => %error,
+            );
+        }
+
+        self
+    }
+
+    // for composing with other layers, `with_tracing` is enough when this is the only one
+    #[cfg(feature = "tracing")]
+    pub fn tracing_layer(&self) -> tracing::SloggerLayer {
+        tracing::SloggerLayer::new(self.logger.as_ref().clone())
+    }
+
+    #[cfg(feature = "tracing")]
+    pub fn with_tracing(self) -> Self {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let subscriber = tracing_subscriber::registry().with(self.tracing_layer());
+
+        if let Err(error) = ::tracing::subscriber::set_global_default(subscriber) {
+            warn!(
+                &self.logger,
+                "Tracing Subscriber Not Installed";
+                "error" => %error,
+            );
+        }
+
+        self
+    }
+
     #[cfg(feature = "callbacks")]
     pub fn on_request(
-        mut self,
+        self,
         handler: impl for<'r> Fn(
                 Arc<Logger>,
                 &'r mut Request<'_>,
@@ -201,13 +705,12 @@ impl Slogger {
             + Sync
             + 'static,
     ) -> Self {
-        self.request_handlers.push(Arc::new(handler));
-        self
+        self.enrich_request(callbacks::BoxedRequestHandler(handler))
     }
 
     #[cfg(feature = "callbacks")]
     pub fn on_response(
-        mut self,
+        self,
         handler: impl for<'r> Fn(
                 Arc<Logger>,
                 &'r Request<'_>,
@@ -218,7 +721,125 @@ impl Slogger {
             + Sync
             + 'static,
     ) -> Self {
-        self.response_handlers.push(Arc::new(handler));
+        self.enrich_response(callbacks::BoxedResponseHandler(handler))
+    }
+
+    // plain `async move` closures work here, at the cost of an owned copy of the request
+    #[cfg(feature = "callbacks")]
+    pub fn on_request_async<Fut>(
+        self,
+        handler: impl Fn(Arc<Logger>, callbacks::RequestInfo) -> Fut + Send + Sync + 'static,
+    ) -> Self
+    where
+        Fut: Future<Output = Option<Arc<Logger>>> + Send + 'static,
+    {
+        self.enrich_request(callbacks::AsyncRequestHandler(handler))
+    }
+
+    #[cfg(feature = "callbacks")]
+    pub fn on_response_async<Fut>(
+        self,
+        handler: impl Fn(Arc<Logger>, callbacks::ResponseInfo) -> Fut + Send + Sync + 'static,
+    ) -> Self
+    where
+        Fut: Future<Output = Option<Arc<Logger>>> + Send + 'static,
+    {
+        self.enrich_response(callbacks::AsyncResponseHandler(handler))
+    }
+
+    // fields are collected across all callbacks, which can read or replace fields set by earlier ones,
+    // then added to the logger once instead of each callback creating a new logger
+    #[cfg(feature = "callbacks")]
+    pub fn on_request_fields<Fut>(
+        self,
+        handler: impl Fn(fields::RequestFields, callbacks::RequestInfo) -> Fut + Send + Sync + 'static,
+    ) -> Self
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.enrich_request(callbacks::AsyncRequestFieldsHandler(handler))
+    }
+
+    // fields set at request time are still there, so they can be read or replaced here too
+    #[cfg(feature = "callbacks")]
+    pub fn on_response_fields<Fut>(
+        self,
+        handler: impl Fn(fields::RequestFields, callbacks::ResponseInfo) -> Fut + Send + Sync + 'static,
+    ) -> Self
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.enrich_response(callbacks::AsyncResponseFieldsHandler(handler))
+    }
+
+    // for callbacks that may suppress the record or change its level or message
+    #[cfg(feature = "callbacks")]
+    pub fn on_request_decision<Fut>(
+        self,
+        handler: impl Fn(Arc<Logger>, callbacks::RequestInfo) -> Fut + Send + Sync + 'static,
+    ) -> Self
+    where
+        Fut: Future<Output = callbacks::LogDecision> + Send + 'static,
+    {
+        self.enrich_request(callbacks::AsyncRequestDecisionHandler(handler))
+    }
+
+    #[cfg(feature = "callbacks")]
+    pub fn on_response_decision<Fut>(
+        self,
+        handler: impl Fn(Arc<Logger>, callbacks::ResponseInfo) -> Fut + Send + Sync + 'static,
+    ) -> Self
+    where
+        Fut: Future<Output = callbacks::LogDecision> + Send + 'static,
+    {
+        self.enrich_response(callbacks::AsyncResponseDecisionHandler(handler))
+    }
+
+    #[cfg(feature = "callbacks")]
+    pub fn on_ignite(
+        mut self,
+        handler: impl for<'r> Fn(Arc<Logger>, &'r Rocket<Build>) -> BoxFuture<'r, ()>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.ignite_handlers.push(Arc::new(handler));
+        self
+    }
+
+    #[cfg(feature = "callbacks")]
+    pub fn on_liftoff(
+        mut self,
+        handler: impl for<'r> Fn(Arc<Logger>, &'r Rocket<Orbit>) -> BoxFuture<'r, Option<Arc<Logger>>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.liftoff_handlers.push(Arc::new(handler));
+        self
+    }
+
+    #[cfg(feature = "callbacks")]
+    pub fn on_shutdown(
+        mut self,
+        handler: impl for<'r> Fn(Arc<Logger>, &'r Rocket<Orbit>) -> BoxFuture<'r, Option<Arc<Logger>>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.shutdown_handlers.push(Arc::new(handler));
+        self
+    }
+
+    #[cfg(feature = "callbacks")]
+    pub fn enrich_request(mut self, enricher: impl callbacks::RequestEnricher) -> Self {
+        self.request_handlers.push(Arc::new(enricher));
+        self
+    }
+
+    #[cfg(feature = "callbacks")]
+    pub fn enrich_response(mut self, enricher: impl callbacks::ResponseEnricher) -> Self {
+        self.response_handlers.push(Arc::new(enricher));
         self
     }
 }
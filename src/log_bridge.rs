@@ -0,0 +1,77 @@
+use crate::kv::strip_ansi_codes;
+use slog::Logger;
+
+pub use log::{LevelFilter, SetLoggerError};
+
+// forwards records from the `log` crate, which Rocket and many libraries log through,
+// into a slog logger with where they came from kept as key-value pairs
+pub struct LogBridge {
+    logger: Logger,
+    level: LevelFilter,
+}
+
+impl LogBridge {
+    pub fn new(logger: Logger) -> Self {
+        Self {
+            logger,
+            level: LevelFilter::Trace,
+        }
+    }
+
+    // fails if any other `log` backend was installed first, such as Rocket's own logger
+    // which is installed by `rocket::build()` and `rocket::custom()`
+    pub fn install(mut self, level: LevelFilter) -> Result<(), SetLoggerError> {
+        self.level = level;
+        log::set_boxed_logger(Box::new(self))?;
+        crate::dispatch::Dispatch::enable();
+
+        // Rocket's messages about how it dispatched each request are info records, which are read for the
+        // response logs whatever the level records are passed on at
+        log::set_max_level(level.max(LevelFilter::Info));
+
+        Ok(())
+    }
+}
+
+impl log::Log for LogBridge {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        // level filtering is left to `log::set_max_level`, `log` below and the slog drain
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        // Rocket's indented messages end their target with `::_`, including those of the code generated for
+        // routes, which is targeted at the module of the route
+        let from_rocket = record.target().starts_with("rocket") || record.target().ends_with("::_");
+        let passed_on = record.level() <= self.level;
+        if !from_rocket && !passed_on {
+            return;
+        }
+
+        let message = strip_ansi_codes(&record.args().to_string());
+        if from_rocket {
+            crate::dispatch::Dispatch::observe(&message);
+        }
+
+        if !passed_on {
+            return;
+        }
+
+        let logger = self.logger.new(slog::o!(
+            "target" => record.target().to_string(),
+            "module" => record.module_path().map(|module| module.to_string()),
+            "file" => record.file().map(|file| file.to_string()),
+            "line" => record.line(),
+        ));
+
+        match record.level() {
+            log::Level::Error => slog::error!(logger, "{}", message),
+            log::Level::Warn => slog::warn!(logger, "{}", message),
+            log::Level::Info => slog::info!(logger, "{}", message),
+            log::Level::Debug => slog::debug!(logger, "{}", message),
+            log::Level::Trace => slog::trace!(logger, "{}", message),
+        }
+    }
+
+    fn flush(&self) {}
+}
@@ -0,0 +1,50 @@
+use crate::dispatch::Dispatch;
+use slog::Logger;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::panic::{self, PanicHookInfo};
+use std::sync::{Arc, Once};
+
+// only the first fairing to ignite installs it, its logger is used for panics outside of requests
+pub(crate) fn install(logger: Arc<Logger>) {
+    static INSTALLED: Once = Once::new();
+
+    INSTALLED.call_once(|| {
+        Dispatch::enable();
+        let previous = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            log_panic(&logger, info);
+            previous(info);
+        }));
+    });
+}
+
+fn log_panic(logger: &Logger, info: &PanicHookInfo<'_>) {
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned());
+
+    // only captured when enabled through `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE`
+    let backtrace = Backtrace::capture();
+    let backtrace = match backtrace.status() {
+        BacktraceStatus::Captured => Some(backtrace.to_string()),
+        _ => None,
+    };
+
+    // panics of a request task are logged with its transaction and route
+    let (logger, route) = match Dispatch::current() {
+        Some((logger, route)) => (Logger::clone(&logger), route),
+        None => (logger.clone(), None),
+    };
+
+    slog::crit!(
+        logger,
+        "Panic";
+        "backtrace" => backtrace,
+        "route" => route,
+        "location" => info.location().map(|location| location.to_string()),
+        "message" => message,
+    );
+}
@@ -0,0 +1,475 @@
+use regex::{Captures, Regex};
+use rocket::http::RawStr;
+use slog::{Drain, Key, OwnedKV, OwnedKVList, Record, RecordStatic, Serializer, KV};
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+// values that look sensitive wherever they are logged
+#[derive(Clone, Debug)]
+pub enum Detector {
+    Email,
+    // digit runs passing the Luhn check, so that other long numbers are left alone
+    CreditCard,
+    Jwt,
+    Custom(Regex),
+}
+
+impl Detector {
+    fn regex(&self) -> Option<Regex> {
+        let pattern = match self {
+            Self::Email => r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}",
+            Self::CreditCard => r"\b\d(?:[ -]?\d){12,18}\b",
+            Self::Jwt => r"\beyJ[A-Za-z0-9_-]*\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*",
+            Self::Custom(regex) => return Some(regex.clone()),
+        };
+
+        Regex::new(pattern).ok()
+    }
+}
+
+// what to mask before records reach the drain, applied to the values of every record whether logged
+// by the fairing, by routes or added by callbacks
+#[derive(Clone, Debug)]
+pub struct Redaction {
+    mask: String,
+    // compared without regard to case
+    query_params: Vec<String>,
+    headers: Vec<String>,
+    uri_keys: Vec<&'static str>,
+    paths: Vec<Regex>,
+    detectors: Vec<(Detector, Regex)>,
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Self {
+            mask: "[REDACTED]".to_string(),
+            query_params: vec![],
+            headers: vec![],
+            uri_keys: vec!["uri", "url"],
+            paths: vec![],
+            detectors: vec![],
+        }
+    }
+}
+
+impl Redaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // common credentials and personal details
+    pub fn recommended() -> Self {
+        Self::new()
+            .query_params([
+                "token",
+                "access_token",
+                "api_key",
+                "apikey",
+                "key",
+                "password",
+                "secret",
+            ])
+            .headers([
+                "authorization",
+                "proxy-authorization",
+                "cookie",
+                "set-cookie",
+                "x-api-key",
+            ])
+            .detect(Detector::Email)
+            .detect(Detector::CreditCard)
+            .detect(Detector::Jwt)
+    }
+
+    pub fn mask(mut self, mask: impl Into<String>) -> Self {
+        self.mask = mask.into();
+        self
+    }
+
+    // values of these query parameters are masked in the values of the uri keys
+    pub fn query_param(mut self, name: impl Into<String>) -> Self {
+        self.query_params.push(name.into().to_lowercase());
+        self
+    }
+
+    pub fn query_params<S: Into<String>>(self, names: impl IntoIterator<Item = S>) -> Self {
+        names.into_iter().fold(self, Self::query_param)
+    }
+
+    // values logged under a header's name are masked entirely
+    pub fn header(mut self, name: impl Into<String>) -> Self {
+        self.headers.push(name.into().to_lowercase());
+        self
+    }
+
+    pub fn headers<S: Into<String>>(self, names: impl IntoIterator<Item = S>) -> Self {
+        names.into_iter().fold(self, Self::header)
+    }
+
+    // another key whose values are uris, besides `uri` and `url`
+    pub fn uri_key(mut self, key: &'static str) -> Self {
+        self.uri_keys.push(key);
+        self
+    }
+
+    // the capture groups of the regex, or all of it without any, are masked in the path of uris,
+    // such as `^/users/([^/]+)` to hide user names
+    pub fn path(mut self, regex: Regex) -> Self {
+        self.paths.push(regex);
+        self
+    }
+
+    pub fn detect(mut self, detector: Detector) -> Self {
+        if let Some(regex) = detector.regex() {
+            self.detectors.push((detector, regex));
+        }
+        self
+    }
+
+    pub fn redact<'v>(&self, key: &str, value: &'v str) -> Cow<'v, str> {
+        if self
+            .headers
+            .iter()
+            .any(|header| header.eq_ignore_ascii_case(key))
+        {
+            return Cow::Owned(self.mask.clone());
+        }
+
+        let mut value = Cow::Borrowed(value);
+        if self.uri_keys.contains(&key) {
+            value = self.redact_uri(value);
+        } else if key == "url.query" {
+            value = Cow::Owned(self.redact_query(&value));
+        } else if key == "url.path" {
+            value = Cow::Owned(self.redact_path(&value));
+        } else if key == "url.query_params" {
+            for param in &self.query_params {
+                value = Cow::Owned(mask_json_value(&value, param, &self.mask));
+            }
+        }
+
+        self.mask_detected(value)
+    }
+
+    fn mask_detected<'v>(&self, mut value: Cow<'v, str>) -> Cow<'v, str> {
+        for (detector, regex) in &self.detectors {
+            if let Cow::Owned(redacted) = regex.replace_all(&value, |captures: &Captures| {
+                let found = &captures[0];
+                match detector {
+                    Detector::CreditCard if !passes_luhn(found) => found.to_string(),
+                    _ => self.mask.clone(),
+                }
+            }) {
+                if redacted != *value {
+                    value = Cow::Owned(redacted);
+                }
+            }
+        }
+
+        value
+    }
+
+    fn detects(&self, value: &str) -> bool {
+        matches!(self.mask_detected(Cow::Borrowed(value)), Cow::Owned(_))
+    }
+
+    fn redact_uri<'v>(&self, uri: Cow<'v, str>) -> Cow<'v, str> {
+        if self.paths.is_empty() && self.query_params.is_empty() && self.detectors.is_empty() {
+            return uri;
+        }
+
+        let (path, query) = match uri.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (uri.as_ref(), None),
+        };
+
+        let path = self.redact_path(path);
+        match query {
+            Some(query) => Cow::Owned(format!("{path}?{}", self.redact_query(query))),
+            None => Cow::Owned(path),
+        }
+    }
+
+    fn redact_path(&self, path: &str) -> String {
+        let mut path = path.to_string();
+        for regex in &self.paths {
+            path = mask_captures(regex, &path, &self.mask);
+        }
+        path
+    }
+
+    // names and values are compared as decoded, which is how the application sees them, so values are masked
+    // in full when anything is detected in them
+    fn redact_query(&self, query: &str) -> String {
+        query
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((name, value))
+                    if self.is_sensitive_param(&RawStr::new(name).url_decode_lossy())
+                        || self.detects(&RawStr::new(value).url_decode_lossy()) =>
+                {
+                    format!("{name}={}", self.mask)
+                }
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    // the mask when a path rule masks any of `range` in the path, for values taken from the path elsewhere
+    #[cfg(feature = "url_fields")]
+    pub(crate) fn path_mask(&self, path: &str, range: std::ops::Range<usize>) -> Option<&str> {
+        let overlaps = |found: regex::Match| found.start() < range.end && range.start < found.end();
+
+        self.paths
+            .iter()
+            .any(|regex| {
+                regex
+                    .captures_iter(path)
+                    .any(|captures| match captures.len() {
+                        1 => captures.get(0).is_some_and(overlaps),
+                        _ => captures.iter().skip(1).flatten().any(overlaps),
+                    })
+            })
+            .then_some(self.mask.as_str())
+    }
+
+    fn is_sensitive_param(&self, name: &str) -> bool {
+        self.query_params
+            .iter()
+            .any(|param| param.eq_ignore_ascii_case(name))
+    }
+}
+
+fn mask_captures(regex: &Regex, value: &str, mask: &str) -> String {
+    regex
+        .replace_all(value, |captures: &Captures| {
+            let Some(whole) = captures.get(0).filter(|_| captures.len() > 1) else {
+                return mask.to_string();
+            };
+
+            // rebuilds the match with each participating group replaced
+            let mut masked = String::new();
+            let mut last = whole.start();
+            for group in captures.iter().skip(1).flatten() {
+                masked.push_str(&value[last..group.start()]);
+                masked.push_str(mask);
+                last = group.end();
+            }
+            masked.push_str(&value[last..whole.end()]);
+            masked
+        })
+        .into_owned()
+}
+
+// masks the values of a key in the flat JSON objects of the `url_fields` feature, which are only ever made
+// of strings and arrays of strings, and which have the key as many times as the query repeats it
+fn mask_json_value(json: &str, key: &str, mask: &str) -> String {
+    let needle = format!("\"{key}\":");
+    let mut masked = json.to_string();
+    let mut from = 0;
+
+    while let Some(start) = masked[from..]
+        .to_ascii_lowercase()
+        .find(&needle)
+        .map(|start| from + start + needle.len())
+    {
+        let rest = &masked[start..];
+        let mut end = rest.len();
+        let (mut in_string, mut escaped, mut depth) = (false, false, 0);
+        for (index, c) in rest.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = !in_string,
+                '[' if !in_string => depth += 1,
+                ']' if !in_string => depth -= 1,
+                ',' | '}' if !in_string && depth == 0 => {
+                    end = index;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        masked = format!("{}\"{mask}\"{}", &masked[..start], &rest[end..]);
+        from = start + mask.len() + 2;
+    }
+
+    masked
+}
+
+fn passes_luhn(number: &str) -> bool {
+    let digits = number
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .collect::<Vec<_>>();
+
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| match index % 2 {
+            1 if digit * 2 > 9 => digit * 2 - 9,
+            1 => digit * 2,
+            _ => *digit,
+        })
+        .sum();
+
+    sum.is_multiple_of(10)
+}
+
+// masks what the redaction asks for before passing records on, which means every value of a record
+// is visited once for each drain it reaches, so it should wrap only the drain that needs it
+pub struct RedactingDrain<D> {
+    drain: D,
+    redaction: Arc<Redaction>,
+}
+
+impl<D> RedactingDrain<D> {
+    pub fn new(drain: D, redaction: Redaction) -> Self {
+        Self::shared(drain, Arc::new(redaction))
+    }
+
+    pub(crate) fn shared(drain: D, redaction: Arc<Redaction>) -> Self {
+        Self { drain, redaction }
+    }
+}
+
+impl<D: Drain> Drain for RedactingDrain<D> {
+    type Ok = D::Ok;
+    type Err = D::Err;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<D::Ok, D::Err> {
+        let kv = Redacted {
+            kv: record.kv(),
+            redaction: &self.redaction,
+        };
+        let record_static = RecordStatic {
+            location: record.location(),
+            tag: record.tag(),
+            level: record.level(),
+        };
+        let record = Record::new(&record_static, record.msg(), slog::BorrowedKV(&kv));
+
+        let values = OwnedKVList::from(OwnedKV(RedactedValues {
+            values: values.clone(),
+            redaction: self.redaction.clone(),
+        }));
+
+        self.drain.log(&record, &values)
+    }
+}
+
+struct Redacted<'a, K> {
+    kv: K,
+    redaction: &'a Redaction,
+}
+
+impl<K: KV> KV for Redacted<'_, K> {
+    fn serialize(&self, record: &Record, serializer: &mut dyn Serializer) -> slog::Result {
+        self.kv.serialize(
+            record,
+            &mut RedactingSerializer {
+                serializer,
+                redaction: self.redaction,
+            },
+        )
+    }
+}
+
+struct RedactedValues {
+    values: OwnedKVList,
+    redaction: Arc<Redaction>,
+}
+
+impl KV for RedactedValues {
+    fn serialize(&self, record: &Record, serializer: &mut dyn Serializer) -> slog::Result {
+        self.values.serialize(
+            record,
+            &mut RedactingSerializer {
+                serializer,
+                redaction: &self.redaction,
+            },
+        )
+    }
+}
+
+struct RedactingSerializer<'a, 's> {
+    serializer: &'s mut dyn Serializer,
+    redaction: &'a Redaction,
+}
+
+// integers are redacted as their decimal rendering, for values logged under sensitive names and for card
+// numbers, keeping their types when nothing is masked
+macro_rules! redact_integers {
+    ($($method:ident: $type:ty),* $(,)?) => {
+        $(
+            fn $method(&mut self, key: Key, value: $type) -> slog::Result {
+                let rendered = value.to_string();
+                match self.redaction.redact(key, &rendered) {
+                    Cow::Owned(redacted) if redacted != rendered => {
+                        self.serializer.emit_str(key, &redacted)
+                    }
+                    _ => self.serializer.$method(key, value),
+                }
+            }
+        )*
+    };
+}
+
+// values that can not hold anything sensitive keep their types
+macro_rules! pass_through {
+    ($($method:ident: $type:ty),* $(,)?) => {
+        $(
+            fn $method(&mut self, key: Key, value: $type) -> slog::Result {
+                self.serializer.$method(key, value)
+            }
+        )*
+    };
+}
+
+impl Serializer for RedactingSerializer<'_, '_> {
+    redact_integers!(
+        emit_usize: usize,
+        emit_isize: isize,
+        emit_u8: u8,
+        emit_i8: i8,
+        emit_u16: u16,
+        emit_i16: i16,
+        emit_u32: u32,
+        emit_i32: i32,
+        emit_u64: u64,
+        emit_i64: i64,
+        emit_u128: u128,
+        emit_i128: i128,
+    );
+
+    pass_through!(
+        emit_bool: bool,
+        emit_char: char,
+        emit_f32: f32,
+        emit_f64: f64,
+    );
+
+    fn emit_unit(&mut self, key: Key) -> slog::Result {
+        self.serializer.emit_unit(key)
+    }
+
+    fn emit_none(&mut self, key: Key) -> slog::Result {
+        self.serializer.emit_none(key)
+    }
+
+    fn emit_str(&mut self, key: Key, value: &str) -> slog::Result {
+        self.serializer
+            .emit_str(key, &self.redaction.redact(key, value))
+    }
+
+    fn emit_arguments(&mut self, key: Key, value: &fmt::Arguments) -> slog::Result {
+        let value = value.to_string();
+        self.serializer
+            .emit_str(key, &self.redaction.redact(key, &value))
+    }
+}
@@ -0,0 +1,104 @@
+use crate::dispatch::{Dispatch, SeenRoutes};
+use crate::fields::RequestFields;
+use crate::from_request::GuardFallback;
+use crate::{log_fields, Logger, Slogger};
+use rocket::{Request, Route};
+use std::sync::{Arc, Mutex, OnceLock};
+
+// the logger built for each request by the fairing, including anything added by callbacks, which can
+// look for it before it is attached, so the cache is only ever filled by attaching
+pub(crate) struct RequestLogger(OnceLock<Arc<Logger>>);
+
+impl RequestLogger {
+    pub fn attach_on(logger: Arc<Logger>, request: &Request<'_>) {
+        let _ = Self::cache(request).0.set(logger);
+    }
+
+    pub fn get(request: &Request<'_>) -> Option<Arc<Logger>> {
+        Self::cache(request).0.get().cloned()
+    }
+
+    fn cache<'r>(request: &'r Request<'_>) -> &'r Self {
+        request.local_cache(|| Self(OnceLock::new()))
+    }
+
+    // with the details of the route currently handling the request, which is only known once routed and
+    // can change when a route forwards, so it is kept along with the route it was built for
+    pub fn get_routed(request: &Request<'_>) -> Option<Arc<Logger>> {
+        // by the address of the route, which is only ever compared
+        struct RoutedLogger(Mutex<Option<(usize, Arc<Logger>)>>);
+
+        let logger = Self::get(request)?;
+        let Some(route) = request.route() else {
+            return Some(logger);
+        };
+        let address = route as *const Route as usize;
+
+        let cache = request.local_cache(|| RoutedLogger(Mutex::new(None)));
+        let mut cached = cache
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        match &*cached {
+            Some((cached, routed)) if *cached == address => Some(routed.clone()),
+            _ => {
+                SeenRoutes::record(request, route);
+                Dispatch::attach_route(request, route);
+
+                let routed = Arc::new(Slogger::new_logger_with_route_details(
+                    &logger, request, route,
+                ));
+                *cached = Some((address, routed.clone()));
+                Some(routed)
+            }
+        }
+    }
+}
+
+// gives access to the request's logger anywhere a `Request` is at hand,
+// such as in catchers, other fairings and responders
+pub trait RequestSloggerExt {
+    fn logger(&self) -> Slogger;
+}
+
+impl RequestSloggerExt for Request<'_> {
+    fn logger(&self) -> Slogger {
+        let logger = RequestLogger::get_routed(self).unwrap_or_else(|| {
+            // requests that never went through the fairing, which is most likely not attached
+            match self.rocket().state::<Slogger>() {
+                Some(slogger) => Arc::new(slogger.get_for_request(self)),
+                None => {
+                    GuardFallback::report_missing_fairing(self);
+
+                    // never fails, so discards unless another logger was given as the fallback
+                    Arc::new(
+                        GuardFallback::get()
+                            .logger()
+                            .unwrap_or_else(|| Logger::root(slog::Discard, log_fields!())),
+                    )
+                }
+            }
+        });
+
+        Slogger::from_request_logger(logger, self)
+    }
+}
+
+impl Slogger {
+    pub(crate) fn from_request_logger(logger: Arc<Logger>, request: &Request<'_>) -> Self {
+        let mut slogger = Slogger::from_logger(Logger::clone(&logger));
+        slogger.fields = Some(RequestFields::of(request).clone());
+
+        #[cfg(feature = "audit")]
+        {
+            slogger.audit_logger = request
+                .rocket()
+                .state::<Slogger>()
+                .and_then(|fairing| fairing.audit.as_ref())
+                .map(|audit| audit.logger_for(request));
+        }
+
+        slogger
+    }
+}
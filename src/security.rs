@@ -0,0 +1,285 @@
+use rocket::http::{RawStr, Status};
+use rocket::Request;
+use slog::Logger;
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// what a security event was raised for, logged as its `classification` field
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Classification {
+    PathTraversal,
+    Scanner,
+    LongUri,
+    LongHeader,
+    RepeatedAuthFailures,
+}
+
+impl Classification {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PathTraversal => "path_traversal",
+            Self::Scanner => "scanner",
+            Self::LongUri => "long_uri",
+            Self::LongHeader => "long_header",
+            Self::RepeatedAuthFailures => "repeated_auth_failures",
+        }
+    }
+}
+
+// user-agents of common vulnerability scanners, compared without regard to case
+pub const SCANNER_AGENTS: &[&str] = &[
+    "sqlmap",
+    "nikto",
+    "nmap",
+    "masscan",
+    "zgrab",
+    "nuclei",
+    "dirbuster",
+    "gobuster",
+    "wpscan",
+    "acunetix",
+    "nessus",
+    "openvas",
+    "w3af",
+    "fuzz faster u fool",
+    "wfuzz",
+];
+
+// which requests get a `Security Event` warning, as a signal to look into rather than a decision to block
+#[derive(Debug)]
+pub struct SecurityRules {
+    path_traversal: bool,
+    scanner_agents: Vec<String>,
+    max_uri_length: Option<usize>,
+    max_header_length: Option<usize>,
+    auth_failures: Option<(usize, Duration)>,
+    max_tracked_clients: usize,
+    remote_address: bool,
+    failures: Mutex<Failures>,
+}
+
+// the recent auth failures of each client, and when those that stopped failing were last let go of
+#[derive(Debug, Default)]
+struct Failures {
+    clients: HashMap<IpAddr, VecDeque<Instant>>,
+    pruned_at: Option<Instant>,
+}
+
+impl Failures {
+    fn prune(&mut self, now: Instant, window: Duration) {
+        self.clients.retain(|_, times| {
+            times
+                .back()
+                .is_some_and(|last| now.duration_since(*last) < window)
+        });
+        self.pruned_at = Some(now);
+    }
+}
+
+impl Default for SecurityRules {
+    fn default() -> Self {
+        Self::none()
+            .path_traversal(true)
+            .scanner_agents(SCANNER_AGENTS.iter().copied())
+            .max_uri_length(2048)
+            .max_header_length(8192)
+            .auth_failures(10, Duration::from_secs(60))
+    }
+}
+
+impl SecurityRules {
+    // every rule with its default threshold
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // no rules, for picking only some of them
+    pub fn none() -> Self {
+        Self {
+            path_traversal: false,
+            scanner_agents: vec![],
+            max_uri_length: None,
+            max_header_length: None,
+            auth_failures: None,
+            max_tracked_clients: 10_000,
+            remote_address: false,
+            failures: Mutex::new(Failures::default()),
+        }
+    }
+
+    // `..` segments in the path or query, also when percent encoded, and encoded null bytes
+    pub fn path_traversal(mut self, enabled: bool) -> Self {
+        self.path_traversal = enabled;
+        self
+    }
+
+    // replaces the user-agents looked for, each matching when found anywhere in the header
+    pub fn scanner_agents<S: Into<String>>(mut self, agents: impl IntoIterator<Item = S>) -> Self {
+        self.scanner_agents = agents
+            .into_iter()
+            .map(|agent| agent.into().to_lowercase())
+            .collect();
+        self
+    }
+
+    pub fn max_uri_length(mut self, length: usize) -> Self {
+        self.max_uri_length = Some(length);
+        self
+    }
+
+    // the length of each header value
+    pub fn max_header_length(mut self, length: usize) -> Self {
+        self.max_header_length = Some(length);
+        self
+    }
+
+    // raised once a client ip gets `count` responses of 401 or 403 within `window`,
+    // then again after as many more
+    pub fn auth_failures(mut self, count: usize, window: Duration) -> Self {
+        self.auth_failures = Some((count.max(1), window));
+        self
+    }
+
+    // how many client ips have their auth failures counted at once, 10000 by default, past which
+    // new clients are only counted as the others stop failing
+    pub fn max_tracked_clients(mut self, count: usize) -> Self {
+        self.max_tracked_clients = count;
+        self
+    }
+
+    // counts auth failures by the address of the connection rather than `client_ip`, which takes the
+    // `X-Real-IP` header when sent, for servers not behind a proxy that sets it
+    pub fn remote_address(mut self, enabled: bool) -> Self {
+        self.remote_address = enabled;
+        self
+    }
+
+    pub(crate) fn inspect_request(&self, logger: &Logger, request: &Request<'_>) {
+        let uri = request.uri().to_string();
+
+        if self.path_traversal && is_path_traversal(&uri) {
+            log_event(logger, request, Classification::PathTraversal, None);
+        }
+
+        if let Some(agent) = request.headers().get_one("User-Agent") {
+            let agent = agent.to_lowercase();
+            if let Some(scanner) = self
+                .scanner_agents
+                .iter()
+                .find(|scanner| agent.contains(scanner.as_str()))
+            {
+                log_event(
+                    logger,
+                    request,
+                    Classification::Scanner,
+                    Some(scanner.clone()),
+                );
+            }
+        }
+
+        if self.max_uri_length.is_some_and(|max| uri.len() > max) {
+            log_event(
+                logger,
+                request,
+                Classification::LongUri,
+                Some(uri.len().to_string()),
+            );
+        }
+
+        if let Some(max) = self.max_header_length {
+            for header in request.headers().iter() {
+                if header.value().len() > max {
+                    let detail = format!("{}: {}", header.name(), header.value().len());
+                    log_event(logger, request, Classification::LongHeader, Some(detail));
+                }
+            }
+        }
+    }
+
+    pub(crate) fn inspect_response(&self, logger: &Logger, request: &Request<'_>, status: Status) {
+        let ip = match self.remote_address {
+            true => request.remote().map(|remote| remote.ip()),
+            false => request.client_ip(),
+        };
+        let (Some((count, window)), Some(ip)) = (self.auth_failures, ip) else {
+            return;
+        };
+        if status != Status::Unauthorized && status != Status::Forbidden {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut failures = self
+            .failures
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        // keeps clients that stopped failing from piling up, looking through them at most once a window
+        // unless there is no room for a new one
+        let has_room = |failures: &Failures| {
+            failures.clients.len() < self.max_tracked_clients || failures.clients.contains_key(&ip)
+        };
+        if !has_room(&failures)
+            || failures
+                .pruned_at
+                .is_none_or(|pruned_at| now.duration_since(pruned_at) >= window)
+        {
+            failures.prune(now, window);
+        }
+        if !has_room(&failures) {
+            return;
+        }
+
+        let times = failures.clients.entry(ip).or_default();
+        times.push_back(now);
+        while times
+            .front()
+            .is_some_and(|first| now.duration_since(*first) >= window)
+        {
+            times.pop_front();
+        }
+
+        if times.len() >= count {
+            let detail = format!("{} within {}s", times.len(), window.as_secs());
+            times.clear();
+            drop(failures);
+
+            log_event(
+                logger,
+                request,
+                Classification::RepeatedAuthFailures,
+                Some(detail),
+            );
+        }
+    }
+}
+
+fn is_path_traversal(uri: &str) -> bool {
+    // decoded twice, for double encoding such as `%252e%252e`
+    let once = RawStr::new(uri).percent_decode_lossy();
+    let twice = RawStr::new(&once).percent_decode_lossy();
+
+    [uri, &once, &twice].iter().any(|uri| {
+        uri.contains('\0')
+            || uri
+                .split(['/', '\\', '?', '&', '='])
+                .any(|segment| segment == "..")
+    })
+}
+
+fn log_event(
+    logger: &Logger,
+    request: &Request<'_>,
+    classification: Classification,
+    detail: Option<String>,
+) {
+    slog::warn!(
+        logger,
+        "Security Event";
+        "client_ip" => request.client_ip().map(|ip| ip.to_string()),
+        "detail" => detail,
+        "classification" => classification.as_str(),
+    );
+}
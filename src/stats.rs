@@ -0,0 +1,255 @@
+use crate::fairing::json_string;
+use rocket::http::Status;
+use rocket::tokio::time::{self, MissedTickBehavior};
+use rocket::{Orbit, Request, Rocket};
+use slog::Logger;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// the latencies kept of each route over an interval, past which the oldest are replaced
+const MAX_SAMPLES: usize = 10_000;
+
+// summaries of the requests responded to over each interval, logged from a task started on liftoff
+pub(crate) struct Stats {
+    interval: Duration,
+    in_flight: AtomicI64,
+    window: Mutex<Window>,
+}
+
+#[derive(Debug, Default)]
+struct Window {
+    requests: u64,
+    // 1xx to 5xx
+    status_classes: [u64; 5],
+    // by route name or path
+    latencies: BTreeMap<String, Latencies>,
+}
+
+#[derive(Debug, Default)]
+struct Latencies {
+    count: u64,
+    // in nanoseconds, the last `MAX_SAMPLES` of the interval
+    samples: Vec<u64>,
+}
+
+impl Latencies {
+    fn push(&mut self, nanos: u64) {
+        match self.samples.len() < MAX_SAMPLES {
+            true => self.samples.push(nanos),
+            false => self.samples[(self.count % MAX_SAMPLES as u64) as usize] = nanos,
+        }
+        self.count += 1;
+    }
+}
+
+// when the request reached the fairing, which does not rely on the `transactions` feature
+struct Started(Instant);
+
+impl Stats {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            // tokio panics on intervals of zero
+            interval: interval.max(Duration::from_millis(1)),
+            in_flight: AtomicI64::new(0),
+            window: Mutex::new(Window::default()),
+        }
+    }
+
+    pub fn request_started(&self, request: &Request<'_>) {
+        request.local_cache(|| Started(Instant::now()));
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn request_finished(&self, request: &Request<'_>, status: Status) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+
+        let elapsed = request.local_cache(|| Started(Instant::now())).0.elapsed();
+        let route = request.route().map(|route| match &route.name {
+            Some(name) => name.to_string(),
+            None => route.uri.to_string(),
+        });
+
+        self.record(status, route, elapsed);
+    }
+
+    fn record(&self, status: Status, route: Option<String>, elapsed: Duration) {
+        let mut window = self
+            .window
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        window.requests += 1;
+        if let Some(count) = window
+            .status_classes
+            .get_mut((status.code / 100).saturating_sub(1) as usize)
+        {
+            *count += 1;
+        }
+
+        // requests that never matched a route are only counted
+        if let Some(route) = route {
+            let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+            window.latencies.entry(route).or_default().push(nanos);
+        }
+    }
+
+    // runs until Rocket starts shutting down
+    pub fn spawn(self: Arc<Self>, logger: Arc<Logger>, rocket: &Rocket<Orbit>) {
+        let shutdown = rocket.shutdown();
+
+        rocket::tokio::spawn(async move {
+            let mut ticks = time::interval(self.interval);
+            ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            // the first tick completes right away
+            ticks.tick().await;
+            let mut since = Instant::now();
+
+            loop {
+                rocket::tokio::select! {
+                    _ = ticks.tick() => {},
+                    _ = shutdown.clone() => break,
+                }
+
+                self.log(&logger, since.elapsed());
+                since = Instant::now();
+            }
+        });
+    }
+
+    // the window so far, leaving an empty one for the next interval
+    fn take_window(&self) -> Window {
+        std::mem::take(
+            &mut *self
+                .window
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        )
+    }
+
+    fn log(&self, logger: &Logger, elapsed: Duration) {
+        let window = self.take_window();
+
+        let latency = window
+            .latencies
+            .into_iter()
+            .map(|(route, Latencies { count, mut samples })| {
+                samples.sort_unstable();
+                format!(
+                    r#"{}:{{"count":{},"p50_ms":{},"p95_ms":{},"p99_ms":{}}}"#,
+                    json_string(&route),
+                    count,
+                    percentile_ms(&samples, 50),
+                    percentile_ms(&samples, 95),
+                    percentile_ms(&samples, 99),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let [informational, success, redirection, client_error, server_error] =
+            window.status_classes;
+
+        slog::info!(
+            logger,
+            "Request Statistics";
+            "latency" => format!("{{{}}}", latency.join(",")),
+            "status_5xx" => server_error,
+            "status_4xx" => client_error,
+            "status_3xx" => redirection,
+            "status_2xx" => success,
+            "status_1xx" => informational,
+            "in_flight" => self.in_flight.load(Ordering::Relaxed),
+            "rps" => format!("{:.2}", window.requests as f64 / elapsed.as_secs_f64()),
+            "requests" => window.requests,
+            "interval_ms" => elapsed.as_millis() as u64,
+        );
+    }
+}
+
+// nearest rank of the sorted latencies, in milliseconds with microsecond precision
+fn percentile_ms(sorted: &[u64], percentile: usize) -> String {
+    let rank = (sorted.len() * percentile).div_ceil(100).max(1);
+    let nanos = sorted[rank.min(sorted.len()) - 1];
+
+    format!("{:.3}", nanos as f64 / 1_000_000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_are_of_the_nearest_rank() {
+        let millis = |values: &[u64]| values.iter().map(|ms| ms * 1_000_000).collect::<Vec<_>>();
+
+        assert_eq!(percentile_ms(&millis(&[7]), 50), "7.000");
+        assert_eq!(percentile_ms(&millis(&[7]), 99), "7.000");
+        assert_eq!(percentile_ms(&millis(&[1, 2]), 50), "1.000");
+        assert_eq!(percentile_ms(&millis(&[1, 2]), 95), "2.000");
+
+        let hundred = millis(&(1..=100).collect::<Vec<_>>());
+        assert_eq!(percentile_ms(&hundred, 50), "50.000");
+        assert_eq!(percentile_ms(&hundred, 95), "95.000");
+        assert_eq!(percentile_ms(&hundred, 99), "99.000");
+        assert_eq!(percentile_ms(&hundred, 0), "1.000");
+        assert_eq!(percentile_ms(&hundred, 100), "100.000");
+
+        // one past a multiple of a hundred rounds the rank up
+        let hundred_one = millis(&(1..=101).collect::<Vec<_>>());
+        assert_eq!(percentile_ms(&hundred_one, 99), "100.000");
+        assert_eq!(percentile_ms(&[1_234_567], 50), "1.235");
+    }
+
+    #[test]
+    fn counts_statuses_by_class() {
+        let stats = Stats::new(Duration::from_secs(60));
+        for code in [100, 200, 204, 299, 301, 404, 418, 500, 599, 600] {
+            stats.record(Status::new(code), None, Duration::ZERO);
+        }
+
+        let window = stats.take_window();
+        assert_eq!(window.requests, 10);
+        assert_eq!(window.status_classes, [1, 3, 1, 2, 2]);
+        assert!(window.latencies.is_empty());
+    }
+
+    #[test]
+    fn starts_each_interval_empty() {
+        let stats = Stats::new(Duration::from_secs(60));
+        stats.record(
+            Status::Ok,
+            Some("users".to_string()),
+            Duration::from_millis(3),
+        );
+        stats.record(
+            Status::Ok,
+            Some("users".to_string()),
+            Duration::from_millis(5),
+        );
+
+        let window = stats.take_window();
+        assert_eq!(window.requests, 2);
+        assert_eq!(window.latencies["users"].count, 2);
+        assert_eq!(window.latencies["users"].samples, [3_000_000, 5_000_000]);
+
+        let window = stats.take_window();
+        assert_eq!(window.requests, 0);
+        assert_eq!(window.status_classes, [0; 5]);
+        assert!(window.latencies.is_empty());
+    }
+
+    #[test]
+    fn keeps_the_last_samples_of_busy_routes() {
+        let mut latencies = Latencies::default();
+        for nanos in 0..MAX_SAMPLES as u64 + 3 {
+            latencies.push(nanos);
+        }
+
+        assert_eq!(latencies.count, MAX_SAMPLES as u64 + 3);
+        assert_eq!(latencies.samples.len(), MAX_SAMPLES);
+        assert_eq!(latencies.samples[..3], [10_000, 10_001, 10_002]);
+        assert_eq!(latencies.samples[3], 3);
+    }
+}
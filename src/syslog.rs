@@ -0,0 +1,294 @@
+use crate::kv::KeyValueList;
+use slog::{Drain, Level, OwnedKVList, Record};
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::net::{SocketAddr, TcpStream, UdpSocket};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+#[cfg(unix)]
+use std::path::PathBuf;
+
+// IANA reserves enterprise number 32473 for documentation and examples
+const DEFAULT_SD_ID: &str = "rocket@32473";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Facility {
+    Kern = 0,
+    User = 1,
+    Mail = 2,
+    Daemon = 3,
+    Auth = 4,
+    Syslog = 5,
+    Lpr = 6,
+    News = 7,
+    Uucp = 8,
+    Cron = 9,
+    AuthPriv = 10,
+    Ftp = 11,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+#[derive(Clone, Debug)]
+pub enum Transport {
+    // plain datagrams, one message each
+    Udp(SocketAddr),
+    // octet-counted framing as described by RFC 6587
+    Tcp(SocketAddr),
+    // datagrams to a local socket such as `/dev/log`
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+// how long to wait on a TCP server, so that logging never blocks for long when it is unreachable
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+enum Connection {
+    Udp(UdpSocket, SocketAddr),
+    Tcp(TcpConnection),
+    #[cfg(unix)]
+    Unix(UnixDatagram, PathBuf),
+}
+
+impl Connection {
+    fn open(transport: &Transport) -> io::Result<Self> {
+        Ok(match transport {
+            Transport::Udp(address) => {
+                let bind_address: SocketAddr = if address.is_ipv4() {
+                    ([0, 0, 0, 0], 0).into()
+                } else {
+                    ([0u16; 8], 0).into()
+                };
+
+                Self::Udp(UdpSocket::bind(bind_address)?, *address)
+            }
+            Transport::Tcp(address) => Self::Tcp(TcpConnection {
+                stream: Some(TcpConnection::connect(address)?),
+                address: *address,
+                retry_at: None,
+            }),
+            #[cfg(unix)]
+            Transport::Unix(path) => Self::Unix(UnixDatagram::unbound()?, path.clone()),
+        })
+    }
+
+    fn send(&mut self, message: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Udp(socket, address) => socket.send_to(message, *address).map(|_| ()),
+            #[cfg(unix)]
+            Self::Unix(socket, path) => socket.send_to(message, path.as_path()).map(|_| ()),
+            Self::Tcp(connection) => {
+                let mut framed = format!("{} ", message.len()).into_bytes();
+                framed.extend_from_slice(message);
+
+                connection.send(&framed)
+            }
+        }
+    }
+}
+
+struct TcpConnection {
+    stream: Option<TcpStream>,
+    address: SocketAddr,
+    // no attempt to reconnect is made before then, records logged in the meantime are dropped
+    retry_at: Option<Instant>,
+}
+
+impl TcpConnection {
+    fn connect(address: &SocketAddr) -> io::Result<TcpStream> {
+        let stream = TcpStream::connect_timeout(address, CONNECT_TIMEOUT)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        Ok(stream)
+    }
+
+    fn send(&mut self, framed: &[u8]) -> io::Result<()> {
+        if let Some(stream) = &mut self.stream {
+            if stream.write_all(framed).is_ok() {
+                return Ok(());
+            }
+
+            // part of the frame may already be written, and sending it again would throw off the
+            // octet counting, so the record is dropped and the connection re-established for the next
+            self.stream = None;
+            return self.reconnect();
+        }
+
+        self.reconnect()?;
+        self.send(framed)
+    }
+
+    fn reconnect(&mut self) -> io::Result<()> {
+        if self
+            .retry_at
+            .is_some_and(|retry_at| Instant::now() < retry_at)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "waiting to reconnect to the syslog server",
+            ));
+        }
+
+        match Self::connect(&self.address) {
+            Ok(stream) => {
+                self.stream = Some(stream);
+                self.retry_at = None;
+                Ok(())
+            }
+            Err(error) => {
+                self.retry_at = Some(Instant::now() + RECONNECT_DELAY);
+                Err(error)
+            }
+        }
+    }
+}
+
+pub struct SyslogDrain {
+    connection: Mutex<Connection>,
+    facility: Facility,
+    hostname: String,
+    app_name: String,
+    sd_id: String,
+}
+
+impl SyslogDrain {
+    pub fn new(app_name: &str, transport: Transport) -> io::Result<Self> {
+        Ok(Self {
+            connection: Mutex::new(Connection::open(&transport)?),
+            facility: Facility::User,
+            hostname: local_hostname(),
+            app_name: header_field(app_name, 48),
+            sd_id: DEFAULT_SD_ID.to_string(),
+        })
+    }
+
+    pub fn facility(mut self, facility: Facility) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    pub fn hostname(mut self, hostname: &str) -> Self {
+        self.hostname = header_field(hostname, 255);
+        self
+    }
+
+    // structured data IDs without an `@` are reserved by IANA,
+    // so custom ones should look like `name@<private enterprise number>`
+    pub fn structured_data_id(mut self, sd_id: &str) -> Self {
+        self.sd_id = param_name(sd_id);
+        self
+    }
+
+    fn format(&self, record: &Record, values: &OwnedKVList) -> String {
+        let priority = self.facility as u8 * 8 + severity(record.level());
+        let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, true);
+
+        let mut message = format!(
+            "<{priority}>1 {timestamp} {hostname} {app_name} {procid} - ",
+            hostname = self.hostname,
+            app_name = self.app_name,
+            procid = std::process::id(),
+        );
+
+        let fields = KeyValueList::from_record(record, values).into_inner();
+        if fields.is_empty() {
+            message.push('-');
+        } else {
+            let _ = write!(message, "[{}", self.sd_id);
+            for (key, value) in fields {
+                let _ = write!(message, " {}=\"{}\"", param_name(&key), param_value(&value));
+            }
+            message.push(']');
+        }
+
+        let _ = write!(message, " {}", record.msg());
+        message
+    }
+}
+
+impl Drain for SyslogDrain {
+    type Ok = ();
+    type Err = io::Error;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> io::Result<()> {
+        let message = self.format(record, values);
+
+        match self.connection.lock() {
+            Ok(mut connection) => connection.send(message.as_bytes()),
+            Err(poisoned) => poisoned.into_inner().send(message.as_bytes()),
+        }
+    }
+}
+
+fn severity(level: Level) -> u8 {
+    match level {
+        Level::Critical => 2,
+        Level::Error => 3,
+        Level::Warning => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+fn local_hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .map(|hostname| header_field(hostname.trim(), 255))
+        .unwrap_or_else(|| String::from("-"))
+}
+
+// header fields are printable US-ASCII without spaces, with `-` standing in for empty values
+fn header_field(value: &str, max_length: usize) -> String {
+    let field: String = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max_length)
+        .collect();
+
+    if field.is_empty() {
+        String::from("-")
+    } else {
+        field
+    }
+}
+
+// parameter names may not contain `=`, spaces, `]` or `"` and are limited to 32 characters
+fn param_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| match c {
+            '=' | ']' | '"' => '_',
+            c if c.is_ascii_graphic() => c,
+            _ => '_',
+        })
+        .take(32)
+        .collect();
+
+    if name.is_empty() {
+        String::from("_")
+    } else {
+        name
+    }
+}
+
+fn param_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
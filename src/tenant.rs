@@ -0,0 +1,148 @@
+use rocket::Request;
+use slog::{Drain, FilterLevel, Logger};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+type Resolver = dyn Fn(&Request<'_>) -> Option<String> + Send + Sync + 'static;
+
+// how the records of one tenant's requests are logged
+#[derive(Clone, Default)]
+pub struct Tenant {
+    logger: Option<Logger>,
+    level: Option<FilterLevel>,
+    sample: Option<u64>,
+    requests: Arc<AtomicU64>,
+}
+
+impl Tenant {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // records of the tenant's requests go to this logger instead of the fairing's
+    pub fn logger(mut self, logger: Logger) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
+    // can only make the tenant quieter than its logger, which is the fairing's unless it has its own,
+    // and than the runtime level of the fairing, which applies to every tenant
+    pub fn level(mut self, level: FilterLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    // only one in every `every` requests of the tenant is logged in full,
+    // the others only log warnings and errors
+    pub fn sample(mut self, every: u64) -> Self {
+        self.sample = Some(every.max(1));
+        self
+    }
+
+    fn apply(&self, logger: Logger) -> Logger {
+        let sampled_out = self.sample.is_some_and(|every| {
+            !self
+                .requests
+                .fetch_add(1, Ordering::Relaxed)
+                .is_multiple_of(every)
+        });
+
+        let level = match (self.level, sampled_out) {
+            (Some(level), true) => Some(level.min(FilterLevel::Warning)),
+            (None, true) => Some(FilterLevel::Warning),
+            (level, false) => level,
+        };
+
+        match level {
+            Some(level) => Logger::root(
+                logger
+                    .filter(move |record| level.accepts(record.level()))
+                    .fuse(),
+                slog::o!(),
+            ),
+            None => logger,
+        }
+    }
+}
+
+// finds the tenant of each request, which is added to its loggers as the `tenant` field
+#[derive(Clone)]
+pub struct Tenants {
+    resolver: Arc<Resolver>,
+    tenants: HashMap<String, Tenant>,
+    others: Option<Tenant>,
+}
+
+impl Tenants {
+    pub fn new<F>(resolver: F) -> Self
+    where
+        F: Fn(&Request<'_>) -> Option<String> + Send + Sync + 'static,
+    {
+        Self {
+            resolver: Arc::new(resolver),
+            tenants: HashMap::new(),
+            others: None,
+        }
+    }
+
+    pub fn from_header(name: &'static str) -> Self {
+        Self::new(move |request| {
+            request
+                .headers()
+                .get_one(name)
+                .map(|tenant| tenant.to_string())
+        })
+    }
+
+    // the first label of the host, for hosts with more labels than `labels`,
+    // such as `acme` of `acme.example.com` with 2 labels
+    pub fn from_subdomain(labels: usize) -> Self {
+        Self::new(move |request| {
+            let host = request
+                .host()
+                .map(|host| host.domain().to_string())
+                .or_else(|| {
+                    request
+                        .headers()
+                        .get_one("Host")
+                        .map(|host| host.to_string())
+                })?;
+            let host = host.split(':').next().unwrap_or_default();
+
+            (host.split('.').count() > labels)
+                .then(|| host.split('.').next().unwrap_or_default().to_lowercase())
+        })
+    }
+
+    pub fn tenant(mut self, key: impl Into<String>, tenant: Tenant) -> Self {
+        self.tenants.insert(key.into(), tenant);
+        self
+    }
+
+    // for tenants not given their own settings
+    pub fn others(mut self, tenant: Tenant) -> Self {
+        self.others = Some(tenant);
+        self
+    }
+
+    #[cfg(any(feature = "redaction", feature = "runtime_level"))]
+    pub(crate) fn map_loggers(&mut self, map: impl Fn(Logger) -> Logger) {
+        for tenant in self.tenants.values_mut().chain(self.others.as_mut()) {
+            tenant.logger = tenant.logger.take().map(&map);
+        }
+    }
+
+    pub(crate) fn logger_for(&self, request: &Request<'_>, logger: &Logger) -> Logger {
+        let Some(key) = (self.resolver)(request) else {
+            return logger.clone();
+        };
+
+        let logger = match self.tenants.get(&key).or(self.others.as_ref()) {
+            Some(tenant) => tenant.apply(tenant.logger.clone().unwrap_or_else(|| logger.clone())),
+            None => logger.clone(),
+        };
+
+        logger.new(slog::o!("tenant" => key))
+    }
+}
@@ -0,0 +1,161 @@
+use crate::kv::KeyValueList;
+use slog::{Drain, Level, OwnedKVList, Record};
+use std::io::{self, IsTerminal, Write};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const BLUE: &str = "\x1b[34m";
+const MAGENTA: &str = "\x1b[35m";
+const CYAN: &str = "\x1b[36m";
+
+// fields pulled out of the key-value list and shown up front in a fixed order
+const SUMMARY_KEYS: [&str; 6] = ["method", "uri", "path", "code", "reason", "elapsed_ns"];
+
+pub struct PrettyDrain {
+    color: bool,
+    max_fields: usize,
+}
+
+impl Default for PrettyDrain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrettyDrain {
+    pub fn new() -> Self {
+        Self {
+            color: io::stdout().is_terminal(),
+            max_fields: 4,
+        }
+    }
+
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    // remaining key-values past this count are collapsed into a `+N more` marker
+    pub fn max_fields(mut self, max_fields: usize) -> Self {
+        self.max_fields = max_fields;
+        self
+    }
+
+    fn paint(&self, line: &mut String, style: &str, text: &str) {
+        if self.color {
+            line.push_str(style);
+            line.push_str(text);
+            line.push_str(RESET);
+        } else {
+            line.push_str(text);
+        }
+    }
+
+    fn format(&self, record: &Record, values: &OwnedKVList) -> io::Result<String> {
+        let mut timestamp = Vec::new();
+        slog_term::timestamp_local(&mut timestamp)?;
+
+        let mut line = String::new();
+        self.paint(&mut line, DIM, &String::from_utf8_lossy(&timestamp));
+        line.push(' ');
+        self.paint(
+            &mut line,
+            level_style(record.level()),
+            &format!("{:<5}", record.level().as_short_str()),
+        );
+        line.push(' ');
+        self.paint(&mut line, BOLD, &record.msg().to_string());
+
+        // the first value wins, which is the most specific one for child loggers
+        let mut fields = KeyValueList::from_record(record, values).into_inner();
+        let mut summary: [Option<String>; SUMMARY_KEYS.len()] = Default::default();
+        fields.retain(
+            |(key, value)| match SUMMARY_KEYS.iter().position(|k| k == key) {
+                Some(index) => {
+                    if summary[index].is_none() && value != "None" {
+                        summary[index] = Some(value.clone());
+                    }
+                    false
+                }
+                None => true,
+            },
+        );
+        let [method, uri, path, code, reason, elapsed_ns] = summary;
+
+        if let Some(method) = method {
+            line.push(' ');
+            self.paint(&mut line, CYAN, &method);
+        }
+        if let Some(uri) = uri.or(path) {
+            line.push(' ');
+            line.push_str(&uri);
+        }
+        if let Some(code) = code {
+            line.push(' ');
+            self.paint(&mut line, status_style(&code), &code);
+        }
+        if let Some(reason) = reason {
+            line.push(' ');
+            line.push_str(&reason);
+        }
+        if let Some(elapsed) = elapsed_ns.and_then(|ns| ns.parse::<f64>().ok()) {
+            line.push(' ');
+            self.paint(
+                &mut line,
+                MAGENTA,
+                &format!("{:.3}ms", elapsed / 1_000_000.0),
+            );
+        }
+
+        let hidden = fields.len().saturating_sub(self.max_fields);
+        for (key, value) in fields.into_iter().take(self.max_fields) {
+            line.push(' ');
+            self.paint(&mut line, DIM, &format!("{key}="));
+            line.push_str(&value);
+        }
+        if hidden > 0 {
+            line.push(' ');
+            self.paint(&mut line, DIM, &format!("+{hidden} more"));
+        }
+
+        line.push('\n');
+        Ok(line)
+    }
+}
+
+impl Drain for PrettyDrain {
+    type Ok = ();
+    type Err = io::Error;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> io::Result<()> {
+        let line = self.format(record, values)?;
+
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(line.as_bytes())?;
+        stdout.flush()
+    }
+}
+
+fn level_style(level: Level) -> &'static str {
+    match level {
+        Level::Critical | Level::Error => RED,
+        Level::Warning => YELLOW,
+        Level::Info => GREEN,
+        Level::Debug => BLUE,
+        Level::Trace => DIM,
+    }
+}
+
+fn status_style(code: &str) -> &'static str {
+    match code.as_bytes().first() {
+        Some(b'2') => GREEN,
+        Some(b'3') => CYAN,
+        Some(b'4') => YELLOW,
+        Some(b'5') => RED,
+        _ => RESET,
+    }
+}
@@ -0,0 +1,117 @@
+use crate::kv::KeyValueList;
+use slog::{Drain, Level, OwnedKVList, Record};
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CapturedRecord {
+    pub level: Level,
+    pub message: String,
+    // the record's own key-values first, then those of its logger, all formatted as they would be logged
+    pub fields: Vec<(String, String)>,
+}
+
+impl CapturedRecord {
+    // the value that would be logged, where a record's own value wins over its logger's
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[track_caller]
+    pub fn assert_field(&self, key: &str, value: impl fmt::Display) {
+        let value = value.to_string();
+
+        assert_eq!(
+            self.field(key),
+            Some(value.as_str()),
+            "field `{key}` of the `{}` record",
+            self.message
+        );
+    }
+}
+
+// shared with the drain, so records logged after it was handed out are still seen
+#[derive(Clone, Debug, Default)]
+pub struct Captured {
+    records: Arc<Mutex<Vec<CapturedRecord>>>,
+}
+
+impl Captured {
+    pub fn records(&self) -> Vec<CapturedRecord> {
+        self.lock().clone()
+    }
+
+    // the first record with the message
+    pub fn find(&self, message: &str) -> Option<CapturedRecord> {
+        self.lock()
+            .iter()
+            .find(|record| record.message == message)
+            .cloned()
+    }
+
+    pub fn find_all(&self, message: &str) -> Vec<CapturedRecord> {
+        self.lock()
+            .iter()
+            .filter(|record| record.message == message)
+            .cloned()
+            .collect()
+    }
+
+    // passes if any record has the field with the value
+    #[track_caller]
+    pub fn assert_field(&self, key: &str, value: impl fmt::Display) {
+        let value = value.to_string();
+        let records = self.lock();
+
+        assert!(
+            records
+                .iter()
+                .any(|record| record.field(key) == Some(value.as_str())),
+            "no record has `{key}` set to `{value}`, {} records were captured: {:#?}",
+            records.len(),
+            *records
+        );
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<CapturedRecord>> {
+        self.records
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub struct CapturingDrain {
+    captured: Captured,
+}
+
+impl CapturingDrain {
+    pub fn new(captured: &Captured) -> Self {
+        Self {
+            captured: captured.clone(),
+        }
+    }
+}
+
+impl Drain for CapturingDrain {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<(), slog::Never> {
+        let fields = KeyValueList::from_record(record, values).into_inner();
+
+        self.captured.lock().push(CapturedRecord {
+            level: record.level(),
+            message: record.msg().to_string(),
+            fields,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use crate::dispatch::Dispatch;
+use ::tracing::field::{Field, Visit};
+use ::tracing::span::{Attributes, Id, Record};
+use ::tracing::{Event, Level, Span, Subscriber};
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+use slog::{Key, Logger, Serializer, KV};
+use std::fmt;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+// field names from `tracing` are always static, which is what slog keys need
+#[derive(Clone, Default)]
+struct Fields(Vec<(&'static str, String)>);
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name(), value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.push((field.name(), format!("{value:?}")));
+    }
+}
+
+impl KV for Fields {
+    fn serialize(&self, _: &slog::Record, serializer: &mut dyn Serializer) -> slog::Result {
+        for (key, value) in &self.0 {
+            serializer.emit_str(Key::from(*key), value)?;
+        }
+        Ok(())
+    }
+}
+
+// forwards `tracing` events to a slog logger, with the fields of every span they happened in
+pub struct SloggerLayer {
+    logger: Logger,
+}
+
+impl SloggerLayer {
+    pub fn new(logger: Logger) -> Self {
+        // events are put in the span of the request being handled, which is looked up in the registry
+        Dispatch::enable();
+        Self { logger }
+    }
+}
+
+impl<S> Layer<S> for SloggerLayer
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    fn on_new_span(&self, attributes: &Attributes<'_>, id: &Id, context: Context<'_, S>) {
+        let mut fields = Fields::default();
+        attributes.record(&mut fields);
+
+        if let Some(span) = context.span(id) {
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, context: Context<'_, S>) {
+        if let Some(span) = context.span(id) {
+            if let Some(fields) = span.extensions_mut().get_mut::<Fields>() {
+                values.record(fields);
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, context: Context<'_, S>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+
+        let message = fields
+            .0
+            .iter()
+            .position(|(key, _)| *key == "message")
+            .map(|index| fields.0.remove(index).1)
+            .unwrap_or_default();
+
+        let spans = context
+            .event_scope(event)
+            .map(|scope| scope.from_root().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        // Rocket does not run routes within the request span, so events of the task or thread handling a
+        // request are taken to be within it when they are not already
+        let request = Dispatch::current_span()
+            .filter(|id| !spans.iter().any(|span| span.id() == *id))
+            .and_then(|id| context.span(&id));
+
+        // outer spans first so that inner spans and the event itself take precedence
+        let mut scope_fields = Fields::default();
+        for span in request.into_iter().chain(spans) {
+            if let Some(span_fields) = span.extensions().get::<Fields>() {
+                scope_fields.0.extend(span_fields.0.iter().cloned());
+            }
+        }
+
+        let metadata = event.metadata();
+        let logger = self.logger.new(slog::o!(
+            "target" => metadata.target(),
+            "module" => metadata.module_path(),
+            "file" => metadata.file(),
+            "line" => metadata.line(),
+        ));
+        let logger = logger.new(slog::o!(scope_fields));
+
+        match *metadata.level() {
+            Level::ERROR => slog::error!(logger, "{}", message; fields),
+            Level::WARN => slog::warn!(logger, "{}", message; fields),
+            Level::INFO => slog::info!(logger, "{}", message; fields),
+            Level::DEBUG => slog::debug!(logger, "{}", message; fields),
+            Level::TRACE => slog::trace!(logger, "{}", message; fields),
+        }
+    }
+}
+
+// the span opened for each request by the fairing, which events of the task or thread handling the request
+// are put in, while futures handed off elsewhere have to be instrumented with it
+#[derive(Clone, Debug)]
+pub struct RequestSpan(pub Span);
+
+impl RequestSpan {
+    pub(crate) fn open(request: &Request<'_>) -> Self {
+        let span = ::tracing::info_span!(
+            "request",
+            method = %request.method(),
+            uri = %request.uri(),
+            transaction = ::tracing::field::Empty,
+        );
+
+        #[cfg(feature = "transactions")]
+        span.record(
+            "transaction",
+            crate::transaction::RequestTransaction::new()
+                .attach_on(request)
+                .id_as_string(),
+        );
+
+        Self(span)
+    }
+
+    pub fn attach_on<'r>(self, request: &'r Request<'_>) -> &'r Self {
+        request.local_cache(|| self)
+    }
+
+    pub fn get(request: &Request<'_>) -> Self {
+        request.local_cache(|| Self(Span::none())).clone()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequestSpan {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(Self::get(request))
+    }
+}
@@ -0,0 +1,173 @@
+use crate::fairing::json_string;
+use rocket::{Request, Route};
+use slog::{Record, Serializer, KV};
+
+// the parts of the request's uri, so that records can be grouped by route template and still be filtered
+// on the values of its parameters
+pub(crate) struct UrlFields {
+    scheme: &'static str,
+    host: Option<String>,
+    path: String,
+    query: Option<String>,
+    // JSON objects and arrays, since slog keys can not be made up as requests come in
+    query_params: Option<String>,
+    query_names: Option<String>,
+}
+
+impl UrlFields {
+    pub fn of(request: &Request<'_>) -> Self {
+        let forwarded = request.headers().get_one("X-Forwarded-Proto");
+        let scheme = match forwarded {
+            Some(scheme) if scheme.eq_ignore_ascii_case("https") => "https",
+            Some(_) => "http",
+            None if request.rocket().config().tls_enabled() => "https",
+            None => "http",
+        };
+
+        let mut params = Vec::<(String, Vec<String>)>::new();
+        for field in request.query_fields() {
+            let name = field.name.source().to_string();
+            match params.iter_mut().find(|(existing, _)| *existing == name) {
+                Some((_, values)) => values.push(field.value.to_string()),
+                None => params.push((name, vec![field.value.to_string()])),
+            }
+        }
+
+        let query_params = (!params.is_empty()).then(|| {
+            let pairs = params
+                .iter()
+                .map(|(name, values)| match values.as_slice() {
+                    [value] => format!("{}:{}", json_string(name), json_string(value)),
+                    values => format!("{}:{}", json_string(name), json_array(values)),
+                })
+                .collect::<Vec<_>>();
+
+            format!("{{{}}}", pairs.join(","))
+        });
+
+        let query_names =
+            (!params.is_empty()).then(|| json_array(params.iter().map(|(name, _)| name)));
+
+        Self {
+            scheme,
+            host: request.host().map(|host| host.to_string()).or_else(|| {
+                request
+                    .headers()
+                    .get_one("Host")
+                    .map(|host| host.to_string())
+            }),
+            path: request.uri().path().to_string(),
+            query: request.uri().query().map(|query| query.to_string()),
+            query_params,
+            query_names,
+        }
+    }
+}
+
+impl KV for UrlFields {
+    fn serialize(&self, record: &Record, serializer: &mut dyn Serializer) -> slog::Result {
+        slog::kv!(
+            "url.query_names" => self.query_names.as_deref(),
+            "url.query_params" => self.query_params.as_deref(),
+            "url.query" => self.query.as_deref(),
+            "url.path" => self.path.as_str(),
+            "url.scheme" => self.scheme,
+            "host" => self.host.as_deref(),
+        )
+        .serialize(record, serializer)
+    }
+}
+
+// the values of the route's dynamic path segments by name, `<path..>` segments taking the rest of the path
+pub(crate) struct PathParams(Option<String>);
+
+impl PathParams {
+    pub fn of(route: &Route, request: &Request<'_>) -> Self {
+        let template = route
+            .uri
+            .path()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+        let segments = request.uri().path().segments().collect::<Vec<_>>();
+
+        // where each segment is in the path as received, for the path rules of the redaction
+        #[cfg(feature = "redaction")]
+        let (path, ranges) = {
+            let path = request.uri().path().as_str();
+            let mut ranges = vec![];
+            let mut start = 0;
+            for segment in path.split('/') {
+                if !segment.is_empty() {
+                    ranges.push(start..start + segment.len());
+                }
+                start += segment.len() + 1;
+            }
+            (path, ranges)
+        };
+        #[cfg(feature = "redaction")]
+        let redaction = request
+            .rocket()
+            .state::<crate::Slogger>()
+            .and_then(|slogger| slogger.redaction.as_ref());
+
+        let mut params = vec![];
+        for (index, segment) in template.iter().enumerate() {
+            let Some(name) = segment
+                .strip_prefix('<')
+                .and_then(|name| name.strip_suffix('>'))
+            else {
+                continue;
+            };
+
+            let (name, value, end) = match name.strip_suffix("..") {
+                Some(name) => (
+                    name,
+                    segments.get(index..).map(|rest| rest.join("/")),
+                    segments.len(),
+                ),
+                None => (
+                    name,
+                    segments.get(index).map(|value| value.to_string()),
+                    index + 1,
+                ),
+            };
+
+            // masked in full when a path rule masks any of it in `uri`
+            #[cfg(feature = "redaction")]
+            let value = match (redaction, ranges.get(index..end)) {
+                (Some(redaction), Some([first, .., last] | [first @ last])) => value.map(|value| {
+                    redaction
+                        .path_mask(path, first.start..last.end)
+                        .map_or(value, str::to_string)
+                }),
+                _ => value,
+            };
+
+            #[cfg(not(feature = "redaction"))]
+            let _ = end;
+
+            // `<_>` segments are ignored by the route too
+            if let (false, Some(value)) = (name == "_", value) {
+                params.push(format!("{}:{}", json_string(name), json_string(&value)));
+            }
+        }
+
+        Self((!params.is_empty()).then(|| format!("{{{}}}", params.join(","))))
+    }
+}
+
+impl KV for PathParams {
+    fn serialize(&self, record: &Record, serializer: &mut dyn Serializer) -> slog::Result {
+        slog::kv!("url.path_params" => self.0.as_deref()).serialize(record, serializer)
+    }
+}
+
+fn json_array<S: AsRef<str>>(values: impl IntoIterator<Item = S>) -> String {
+    let values = values
+        .into_iter()
+        .map(|value| json_string(value.as_ref()))
+        .collect::<Vec<_>>();
+
+    format!("[{}]", values.join(","))
+}
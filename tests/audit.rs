@@ -0,0 +1,110 @@
+#![cfg(all(feature = "audit", feature = "redaction", feature = "testing"))]
+
+mod common;
+
+use rocket::{delete, routes};
+use rocket_slogger::audit::{self, Audit, FieldValue};
+use rocket_slogger::redaction::Redaction;
+use rocket_slogger::testing::CapturedRecord;
+use rocket_slogger::{info, Logger, Slogger};
+
+#[delete("/invoices/<id>")]
+fn delete_invoice(log: Slogger, id: u64) -> &'static str {
+    info!(
+        log.audit(),
+        "Invoice Deleted";
+        "invoice" => id,
+        "contact" => "ann@example.com",
+        "note" => None::<&str>,
+    );
+    "deleted"
+}
+
+// captured values are text, `None` being empty, so the types they were logged with are restored by key
+fn field_value(key: &str, value: &str) -> FieldValue {
+    match (key, value) {
+        (_, "") => FieldValue::Null,
+        ("invoice" | "code", value) => FieldValue::Number(value.to_string()),
+        (_, value) => FieldValue::from(value),
+    }
+}
+
+fn recompute(prev_hash: &str, record: &CapturedRecord) -> String {
+    audit::hash_record(
+        prev_hash,
+        record.level.as_str(),
+        &record.message,
+        record
+            .fields
+            .iter()
+            .map(|(key, value)| (key.as_str(), field_value(key, value))),
+    )
+}
+
+#[rocket::async_test]
+async fn chains_the_hashes_of_redacted_records() {
+    let (audit_logger, audited) = Slogger::new_capturing();
+    let audit = Audit::new(Logger::clone(&audit_logger)).mutations();
+    let (client, _) = common::capturing_client(
+        |fairing| {
+            fairing
+                .with_audit(audit)
+                .with_redaction(Redaction::recommended())
+        },
+        routes![delete_invoice],
+    )
+    .await;
+    client.delete("/invoices/42").dispatch().await;
+    client.delete("/invoices/43").dispatch().await;
+
+    let records = audited.records();
+    let messages = records
+        .iter()
+        .map(|record| record.message.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        ["Invoice Deleted", "Audit", "Invoice Deleted", "Audit"]
+    );
+
+    let mut prev_hash = audit::GENESIS_HASH.to_string();
+    for record in &records {
+        record.assert_field(audit::PREV_HASH_KEY, &prev_hash);
+
+        let hash = recompute(&prev_hash, record);
+        record.assert_field(audit::HASH_KEY, &hash);
+        prev_hash = hash;
+    }
+
+    // hashed as it was kept
+    records[0].assert_field("contact", "[REDACTED]");
+
+    // a record removed from the middle no longer links up
+    let hash = recompute(audit::GENESIS_HASH, &records[0]);
+    assert_ne!(records[2].field(audit::PREV_HASH_KEY), Some(hash.as_str()));
+}
+
+#[test]
+fn hashes_the_canonical_record() {
+    let canonical = audit::canonical_record(
+        audit::GENESIS_HASH,
+        "INFO",
+        "Invoice \"Deleted\"",
+        [
+            ("route", FieldValue::Null),
+            ("invoice", FieldValue::from(42_u64)),
+            ("hash", FieldValue::from("left out")),
+            ("invoice", FieldValue::from("shadowed")),
+            ("paid", FieldValue::from(true)),
+            ("ratio", FieldValue::from(0.5)),
+        ],
+    );
+
+    assert_eq!(
+        canonical,
+        format!(
+            r#"{{"fields":{{"invoice":42,"paid":true,"ratio":0.5,"route":null}},"level":"INFO","msg":"Invoice \"Deleted\"","prev_hash":"{}"}}"#,
+            audit::GENESIS_HASH,
+        ),
+    );
+}
@@ -0,0 +1,18 @@
+use rocket::local::asynchronous::Client;
+use rocket::Route;
+use rocket_slogger::testing::Captured;
+use rocket_slogger::Slogger;
+
+// a local client of a rocket with the routes mounted at `/` and a capturing fairing,
+// set up by `configure` before it is attached
+pub async fn capturing_client(
+    configure: impl FnOnce(Slogger) -> Slogger,
+    routes: Vec<Route>,
+) -> (Client, Captured) {
+    let (fairing, captured) = Slogger::new_capturing();
+    let rocket = rocket::build()
+        .attach(configure(fairing))
+        .mount("/", routes);
+
+    (Client::untracked(rocket).await.unwrap(), captured)
+}
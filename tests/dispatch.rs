@@ -0,0 +1,208 @@
+#![cfg(all(feature = "log_bridge", feature = "testing"))]
+
+use rocket::http::Status;
+use rocket::local::asynchronous::Client;
+use rocket::request::{FromRequest, Outcome};
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::TcpStream;
+use rocket::{catch, catchers, get, routes, Build, Config, Request, Rocket};
+use rocket_slogger::log_bridge::LevelFilter;
+use rocket_slogger::testing::{Captured, CapturedRecord};
+use rocket_slogger::{Logger, Slogger};
+use std::net::{Ipv4Addr, TcpListener};
+use std::sync::Once;
+
+// the bridge is process wide and must be installed before Rocket installs its own logger, at a level
+// below Rocket's routing messages to show they are read anyway
+fn install_bridge() {
+    static INSTALLED: Once = Once::new();
+
+    INSTALLED.call_once(|| {
+        let _ = Slogger::from_logger(Logger::root(slog::Discard, slog::o!()))
+            .with_log_bridge(LevelFilter::Error);
+    });
+}
+
+struct Fails;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Fails {
+    type Error = ();
+
+    async fn from_request(_: &'r Request<'_>) -> Outcome<Self, ()> {
+        Outcome::Error((Status::UnprocessableEntity, ()))
+    }
+}
+
+struct Forwards;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Forwards {
+    type Error = ();
+
+    async fn from_request(_: &'r Request<'_>) -> Outcome<Self, ()> {
+        Outcome::Forward(Status::Unauthorized)
+    }
+}
+
+#[get("/strict")]
+fn strict(_guard: Fails) -> &'static str {
+    "never"
+}
+
+#[get("/caught")]
+fn caught(_guard: Fails) -> &'static str {
+    "never"
+}
+
+#[get("/chain")]
+fn first(_guard: Forwards) -> &'static str {
+    "never"
+}
+
+#[get("/chain", rank = 2)]
+fn second() -> &'static str {
+    "second"
+}
+
+#[get("/panic")]
+fn panics() -> &'static str {
+    panic!("on purpose")
+}
+
+#[catch(422)]
+fn unprocessable() -> &'static str {
+    "unprocessable"
+}
+
+fn rocket(config: Config) -> (Rocket<Build>, Captured) {
+    install_bridge();
+
+    let (fairing, captured) = Slogger::new_capturing();
+    let rocket = rocket::custom(config)
+        .attach(fairing)
+        .mount("/", routes![strict, first, second, panics])
+        .mount("/registered", routes![caught])
+        .register("/registered", catchers![unprocessable]);
+
+    (rocket, captured)
+}
+
+fn response(captured: &Captured, uri: &str) -> CapturedRecord {
+    captured
+        .find_all("Response")
+        .into_iter()
+        .find(|record| record.field("uri") == Some(uri))
+        .unwrap_or_else(|| panic!("no response record for {uri}: {:#?}", captured.records()))
+}
+
+// what each request is expected to be logged with, however it was dispatched
+fn assert_dispatch_fields(captured: &Captured) {
+    let not_found = response(captured, "/missing");
+    not_found.assert_field("failure", "no_route");
+    not_found.assert_field("catcher", "rocket_default");
+    not_found.assert_field("catcher_code", 404);
+    not_found.assert_field("error_status", 404);
+
+    let strict = response(captured, "/strict");
+    strict.assert_field("code", 422);
+    strict.assert_field("failure", "guard");
+    strict.assert_field("failed_guard", "Fails");
+    strict.assert_field("catcher", "rocket_default");
+    strict.assert_field("catcher_code", 422);
+
+    let registered = response(captured, "/registered/caught");
+    registered.assert_field("failure", "guard");
+    registered.assert_field("catcher", "unprocessable");
+    registered.assert_field("catcher_code", 422);
+    registered.assert_field("error_status", 422);
+
+    let panicked = response(captured, "/panic");
+    panicked.assert_field("code", 500);
+    panicked.assert_field("failure", "panic");
+    panicked.assert_field("error_status", 500);
+
+    let chained = response(captured, "/chain");
+    chained.assert_field("code", 200);
+    assert_eq!(chained.field("failure"), None);
+    chained.assert_field(
+        "route_chain",
+        concat!(
+            r#"[{"route":"first","rank":-9,"path":"/chain","forward":"request guard `Forwards`","status":401},"#,
+            r#"{"route":"second","rank":2,"path":"/chain","forward":null,"status":null}]"#,
+        ),
+    );
+}
+
+const URIS: [&str; 5] = [
+    "/missing",
+    "/strict",
+    "/registered/caught",
+    "/panic",
+    "/chain",
+];
+
+#[rocket::async_test]
+async fn records_dispatch_details_under_the_local_client() {
+    let (rocket, captured) = rocket(Config::debug_default());
+    let client = Client::untracked(rocket).await.unwrap();
+
+    for uri in URIS {
+        client.get(uri).dispatch().await;
+    }
+
+    assert_dispatch_fields(&captured);
+}
+
+#[test]
+fn records_dispatch_details_under_the_blocking_local_client() {
+    let (rocket, captured) = rocket(Config::debug_default());
+    let client = rocket::local::blocking::Client::untracked(rocket).unwrap();
+
+    // kept alive while the next requests are dispatched on the same thread
+    let responses = URIS
+        .iter()
+        .map(|uri| client.get(*uri).dispatch())
+        .collect::<Vec<_>>();
+
+    assert_dispatch_fields(&captured);
+    drop(responses);
+}
+
+#[rocket::async_test]
+async fn records_dispatch_details_of_a_launched_server() {
+    let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let config = Config {
+        address: Ipv4Addr::LOCALHOST.into(),
+        port,
+        ..Config::debug_default()
+    };
+
+    let (rocket, captured) = rocket(config);
+    let rocket = rocket.ignite().await.unwrap();
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch());
+
+    for uri in URIS {
+        let mut stream = loop {
+            match TcpStream::connect((Ipv4Addr::LOCALHOST, port)).await {
+                Ok(stream) => break stream,
+                Err(_) => rocket::tokio::task::yield_now().await,
+            }
+        };
+
+        let request = format!("GET {uri} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = vec![];
+        stream.read_to_end(&mut response).await.unwrap();
+    }
+
+    shutdown.notify();
+    let _ = server.await;
+
+    assert_dispatch_fields(&captured);
+}
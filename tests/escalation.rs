@@ -0,0 +1,127 @@
+#![cfg(all(feature = "debug_escalation", feature = "testing"))]
+
+mod common;
+
+use rocket::http::{Cookie, Header};
+use rocket::{get, routes};
+use rocket_slogger::escalation::DebugEscalation;
+use rocket_slogger::testing::Captured;
+use rocket_slogger::{debug, Level, Slogger};
+use std::time::Duration;
+
+#[get("/")]
+fn index(log: Slogger) -> &'static str {
+    debug!(log, "Details");
+    "index"
+}
+
+fn escalation(secret: &str) -> DebugEscalation {
+    DebugEscalation::new(secret)
+        .header("X-Debug-Log")
+        .cookie("debug_log")
+}
+
+async fn dispatch(token: Option<String>) -> Captured {
+    dispatch_with(token, None).await
+}
+
+async fn dispatch_with(token: Option<String>, cookie: Option<String>) -> Captured {
+    let (client, captured) = common::capturing_client(
+        |fairing| fairing.with_debug_escalation(escalation("secret")),
+        routes![index],
+    )
+    .await;
+
+    let mut request = client.get("/");
+    if let Some(token) = token {
+        request = request.header(Header::new("X-Debug-Log", token));
+    }
+    if let Some(cookie) = cookie {
+        request = request.cookie(Cookie::new("debug_log", cookie));
+    }
+    request.dispatch().await;
+
+    captured
+}
+
+#[rocket::async_test]
+async fn valid_tokens_let_records_through_at_their_level() {
+    let token = escalation("secret").token(Level::Debug, Duration::from_secs(60));
+    let captured = dispatch(token).await;
+
+    captured
+        .find("Request")
+        .unwrap()
+        .assert_field("debug_escalation", "DEBUG");
+    assert!(captured.find("Details").is_some());
+}
+
+#[rocket::async_test]
+async fn requests_without_a_token_keep_the_normal_level() {
+    let captured = dispatch(None).await;
+
+    assert_eq!(
+        captured.find("Request").unwrap().field("debug_escalation"),
+        None
+    );
+    assert!(captured.find("Details").is_none());
+}
+
+#[rocket::async_test]
+async fn rejects_tokens_signed_with_another_secret() {
+    let token = escalation("other").token(Level::Debug, Duration::from_secs(60));
+    let captured = dispatch(token).await;
+
+    captured
+        .find("Request")
+        .unwrap()
+        .assert_field("debug_escalation", "REJECTED");
+    assert!(captured.find("Details").is_none());
+}
+
+#[rocket::async_test]
+async fn rejects_tampered_and_expired_tokens() {
+    let token = escalation("secret")
+        .token(Level::Debug, Duration::from_secs(60))
+        .unwrap();
+    let tampered = token.replacen("DEBG", "TRCE", 1);
+
+    let expired = escalation("secret")
+        .token(Level::Debug, Duration::ZERO)
+        .unwrap();
+    // expiry is in whole seconds, so the token is only past it after the next second starts
+    rocket::tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    for token in [tampered, expired, "garbage".to_string()] {
+        let captured = dispatch(Some(token)).await;
+
+        captured
+            .find("Request")
+            .unwrap()
+            .assert_field("debug_escalation", "REJECTED");
+        assert!(captured.find("Details").is_none());
+    }
+}
+
+#[rocket::async_test]
+async fn reads_tokens_from_the_cookie() {
+    let token = escalation("secret").token(Level::Debug, Duration::from_secs(60));
+    let captured = dispatch_with(None, token).await;
+
+    captured
+        .find("Request")
+        .unwrap()
+        .assert_field("debug_escalation", "DEBUG");
+    assert!(captured.find("Details").is_some());
+}
+
+#[rocket::async_test]
+async fn prefers_the_header_to_the_cookie() {
+    let token = escalation("secret").token(Level::Debug, Duration::from_secs(60));
+    let captured = dispatch_with(Some("garbage".to_string()), token).await;
+
+    captured
+        .find("Request")
+        .unwrap()
+        .assert_field("debug_escalation", "REJECTED");
+}
@@ -0,0 +1,45 @@
+use rocket_slogger::fields::{FieldValue, RequestFields};
+use rocket_slogger::o;
+
+#[test]
+fn keeps_every_integer_as_a_number() {
+    let fields = RequestFields::default();
+    fields.extend(&o!(
+        "user_id" => 42,
+        "retries" => 3_u8,
+        "offset" => -7_i16,
+        "count" => 9_usize,
+        "big" => 5_u128,
+        "huge" => u128::MAX,
+        "ratio" => 0.5_f32,
+        "name" => "ann",
+    ));
+
+    assert_eq!(fields.get("user_id"), Some(FieldValue::I64(42)));
+    assert_eq!(fields.get("retries"), Some(FieldValue::U64(3)));
+    assert_eq!(fields.get("offset"), Some(FieldValue::I64(-7)));
+    assert_eq!(fields.get("count"), Some(FieldValue::U64(9)));
+    assert_eq!(fields.get("big"), Some(FieldValue::U64(5)));
+    assert_eq!(
+        fields.get("huge"),
+        Some(FieldValue::Str(u128::MAX.to_string()))
+    );
+    assert_eq!(fields.get("ratio"), Some(FieldValue::F64(0.5)));
+    assert_eq!(fields.get("name"), Some(FieldValue::Str("ann".to_string())));
+}
+
+#[test]
+fn replaces_keys_in_place() {
+    let fields = RequestFields::default();
+    fields.insert("first", 1);
+    fields.insert("second", 2);
+    fields.extend(&o!("first" => "one"));
+
+    assert_eq!(fields.len(), 2);
+    assert_eq!(
+        fields.get("first"),
+        Some(FieldValue::Str("one".to_string()))
+    );
+    assert_eq!(fields.remove("second"), Some(FieldValue::I64(2)));
+    assert_eq!(fields.get("second"), None);
+}
@@ -0,0 +1,81 @@
+#![cfg(all(feature = "journald", unix))]
+
+use rocket_slogger::journald::JournaldDrain;
+use rocket_slogger::{error, info, Drain, Logger};
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+
+fn socket_path(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("rocket-slogger-{}-{name}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn receive(listener: &UnixDatagram) -> Vec<u8> {
+    let mut buffer = vec![0; 65536];
+    let length = listener.recv(&mut buffer).unwrap();
+    buffer.truncate(length);
+    buffer
+}
+
+fn lines(payload: &[u8]) -> Vec<String> {
+    String::from_utf8(payload.to_vec())
+        .unwrap()
+        .lines()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+#[test]
+fn sends_fields_in_the_native_protocol() {
+    let path = socket_path("journald-fields");
+    let listener = UnixDatagram::bind(&path).unwrap();
+
+    let drain = JournaldDrain::with_socket("my-app", &path).unwrap();
+    let logger = Logger::root(
+        drain.fuse(),
+        rocket_slogger::o!("content-type" => "text/html"),
+    );
+
+    info!(logger, "Response"; "code" => 200, "_private" => "x", "9lives" => "y");
+    let lines = lines(&receive(&listener));
+
+    assert_eq!(lines[0], "MESSAGE=Response");
+    assert_eq!(lines[1], "PRIORITY=6");
+    assert_eq!(lines[2], "SYSLOG_IDENTIFIER=my-app");
+    assert!(lines[3].starts_with("CODE_FILE=") && lines[3].ends_with("journald.rs"));
+    assert!(lines[4].starts_with("CODE_LINE="));
+    assert_eq!(lines[5], "CODE_MODULE=journald");
+
+    // leading underscores and digits are dropped from field names
+    assert_eq!(
+        &lines[6..],
+        ["LIVES=y", "PRIVATE=x", "CODE=200", "CONTENT_TYPE=text/html"]
+    );
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn sends_multiline_values_length_prefixed() {
+    let path = socket_path("journald-multiline");
+    let listener = UnixDatagram::bind(&path).unwrap();
+
+    let drain = JournaldDrain::with_socket("app", &path).unwrap();
+    let logger = Logger::root(drain.fuse(), rocket_slogger::o!());
+
+    error!(logger, "Panic"; "backtrace" => "first\nsecond");
+    let payload = receive(&listener);
+
+    let value = b"first\nsecond";
+    let mut expected = b"BACKTRACE\n".to_vec();
+    expected.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    expected.extend_from_slice(value);
+    expected.push(b'\n');
+
+    assert!(payload.starts_with(b"MESSAGE=Panic\nPRIORITY=3\n"));
+    assert!(payload.ends_with(&expected));
+
+    let _ = std::fs::remove_file(&path);
+}
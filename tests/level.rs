@@ -0,0 +1,74 @@
+#![cfg(all(feature = "runtime_level", feature = "testing"))]
+
+mod common;
+
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+use rocket::{get, routes};
+use rocket_slogger::level::LevelRouteAuth;
+use rocket_slogger::testing::Captured;
+use rocket_slogger::{debug, FilterLevel, Slogger};
+
+#[get("/")]
+fn index(log: Slogger) -> &'static str {
+    debug!(log, "Details");
+    "index"
+}
+
+async fn client() -> (Client, Captured) {
+    common::capturing_client(
+        |fairing| {
+            fairing
+                .with_runtime_level(FilterLevel::Info)
+                .mount_level_route("/admin/log-level", LevelRouteAuth::bearer("admin-token"))
+        },
+        routes![index],
+    )
+    .await
+}
+
+#[rocket::async_test]
+async fn changes_the_level_for_authorized_requests() {
+    let (client, captured) = client().await;
+
+    client.get("/").dispatch().await;
+    assert!(captured.find("Details").is_none());
+
+    let response = client
+        .put("/admin/log-level?level=debug")
+        .header(Header::new("Authorization", "Bearer admin-token"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = client
+        .get("/admin/log-level")
+        .header(Header::new("Authorization", "Bearer admin-token"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.into_string().await.unwrap().contains("DEBUG"));
+
+    client.get("/").dispatch().await;
+    assert!(captured.find("Details").is_some());
+}
+
+#[rocket::async_test]
+async fn rejects_requests_without_the_token() {
+    let (client, captured) = client().await;
+
+    for authorization in [None, Some("Bearer wrong"), Some("Basic admin-token")] {
+        let mut request = client.put("/admin/log-level?level=debug");
+        if let Some(authorization) = authorization {
+            request = request.header(Header::new("Authorization", authorization));
+        }
+        assert_eq!(request.dispatch().await.status(), Status::Unauthorized);
+    }
+    assert_eq!(
+        client.get("/admin/log-level").dispatch().await.status(),
+        Status::Unauthorized
+    );
+
+    client.get("/").dispatch().await;
+    assert!(captured.find("Details").is_none());
+}
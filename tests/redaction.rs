@@ -0,0 +1,93 @@
+#![cfg(all(feature = "redaction", feature = "url_fields", feature = "testing"))]
+
+mod common;
+
+use regex::Regex;
+use rocket::{get, routes};
+use rocket_slogger::redaction::Redaction;
+use rocket_slogger::testing::Captured;
+use rocket_slogger::{info, Slogger};
+
+#[get("/users/<name>/cards/<id>?<token>&<page>")]
+fn card(log: Slogger, name: &str, id: u64, token: &str, page: u32) -> &'static str {
+    info!(
+        log,
+        "Card Shown";
+        "card" => 4111111111111111_u64,
+        "authorization" => 7_u32,
+        "page" => page,
+        "contact" => format!("{name}@example.com"),
+        "id" => id,
+        "token_length" => token.len(),
+    );
+    "card"
+}
+
+#[get("/search")]
+fn search() -> &'static str {
+    "search"
+}
+
+async fn dispatch(uri: &str) -> Captured {
+    let redaction = Redaction::recommended().path(Regex::new("^/users/([^/]+)").unwrap());
+    let (client, captured) = common::capturing_client(
+        |fairing| fairing.with_redaction(redaction),
+        routes![card, search],
+    )
+    .await;
+    client.get(uri).dispatch().await;
+
+    captured
+}
+
+#[rocket::async_test]
+async fn masks_every_url_field() {
+    let captured = dispatch("/users/ann/cards/12?token=abc&page=2").await;
+    let response = captured.find("Response").unwrap();
+
+    response.assert_field("uri", "/users/[REDACTED]/cards/12?token=[REDACTED]&page=2");
+    response.assert_field("url.path", "/users/[REDACTED]/cards/12");
+    response.assert_field("url.query", "token=[REDACTED]&page=2");
+    response.assert_field("url.query_params", r#"{"token":"[REDACTED]","page":"2"}"#);
+    response.assert_field("url.query_names", r#"["token","page"]"#);
+    response.assert_field("url.path_params", r#"{"name":"[REDACTED]","id":"12"}"#);
+}
+
+#[rocket::async_test]
+async fn masks_the_values_logged_by_routes() {
+    let captured = dispatch("/users/ann/cards/12?token=abc&page=2").await;
+    let record = captured.find("Card Shown").unwrap();
+
+    // integers under sensitive names and card numbers are masked, the others left alone
+    record.assert_field("card", "[REDACTED]");
+    record.assert_field("authorization", "[REDACTED]");
+    record.assert_field("page", 2);
+    record.assert_field("id", 12);
+    record.assert_field("contact", "[REDACTED]");
+    record.assert_field("uri", "/users/[REDACTED]/cards/12?token=[REDACTED]&page=2");
+}
+
+#[rocket::async_test]
+async fn masks_percent_encoded_query_parameters() {
+    let captured = dispatch("/search?email=ann%40example.com&tok%65n=c&q=rust").await;
+    let response = captured.find("Response").unwrap();
+
+    response.assert_field("uri", "/search?email=[REDACTED]&tok%65n=[REDACTED]&q=rust");
+    response.assert_field("url.query", "email=[REDACTED]&tok%65n=[REDACTED]&q=rust");
+    response.assert_field(
+        "url.query_params",
+        r#"{"email":"[REDACTED]","token":"[REDACTED]","q":"rust"}"#,
+    );
+}
+
+#[rocket::async_test]
+async fn masks_every_repeat_of_a_query_parameter() {
+    let captured = dispatch("/search?token=a&Token=b&q=rust").await;
+    let response = captured.find("Response").unwrap();
+
+    response.assert_field("url.query", "token=[REDACTED]&Token=[REDACTED]&q=rust");
+    response.assert_field(
+        "url.query_params",
+        r#"{"token":"[REDACTED]","Token":"[REDACTED]","q":"rust"}"#,
+    );
+}
@@ -0,0 +1,189 @@
+#![cfg(all(feature = "security_events", feature = "testing"))]
+
+mod common;
+
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+use rocket::{get, routes};
+use rocket_slogger::security::SecurityRules;
+use rocket_slogger::testing::Captured;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+#[get("/private")]
+fn private() -> Status {
+    Status::Unauthorized
+}
+
+#[get("/files/<_name>")]
+fn file(_name: &str) -> &'static str {
+    "file"
+}
+
+async fn security_client(rules: SecurityRules) -> (Client, Captured) {
+    let (client, captured) = common::capturing_client(
+        |fairing| fairing.with_security_events(rules),
+        routes![private, file],
+    )
+    .await;
+    captured.clear();
+
+    (client, captured)
+}
+
+fn classifications(captured: &Captured) -> Vec<String> {
+    captured
+        .find_all("Security Event")
+        .iter()
+        .filter_map(|record| record.field("classification").map(str::to_string))
+        .collect()
+}
+
+// a failure from the connection at `remote`, which may claim to be another client with `X-Real-IP`
+async fn fail(client: &Client, remote: &str, real_ip: Option<&str>) {
+    let mut request = client
+        .get("/private")
+        .remote(remote.parse::<SocketAddr>().unwrap());
+    if let Some(real_ip) = real_ip {
+        request = request.header(Header::new("X-Real-IP", real_ip.to_string()));
+    }
+    request.dispatch().await;
+}
+
+#[rocket::async_test]
+async fn flags_path_traversal_in_any_encoding() {
+    for uri in [
+        "/files/..",
+        "/files/%2e%2e",
+        "/files/%252e%252e",
+        "/files/name?path=../secret",
+        "/files/name?path=..",
+        "/files/..%2fsecret",
+        "/files/name%00",
+    ] {
+        let (client, captured) = security_client(SecurityRules::none().path_traversal(true)).await;
+        client.get(uri).dispatch().await;
+
+        assert_eq!(classifications(&captured), ["path_traversal"], "{uri}");
+    }
+
+    for uri in ["/files/a..b", "/files/...", "/files/name?path=a..b"] {
+        let (client, captured) = security_client(SecurityRules::none().path_traversal(true)).await;
+        client.get(uri).dispatch().await;
+
+        assert!(classifications(&captured).is_empty(), "{uri}");
+    }
+}
+
+#[rocket::async_test]
+async fn flags_scanners_and_long_requests() {
+    let rules = SecurityRules::none()
+        .scanner_agents(["Nikto"])
+        .max_uri_length(32)
+        .max_header_length(16);
+    let (client, captured) = security_client(rules).await;
+
+    client
+        .get("/files/name")
+        .header(Header::new("User-Agent", "Mozilla/5.00 (Nikto/2.1.6)"))
+        .dispatch()
+        .await;
+    client
+        .get(format!("/files/{}", "a".repeat(32)))
+        .dispatch()
+        .await;
+    client.get("/files/name").dispatch().await;
+
+    assert_eq!(
+        classifications(&captured),
+        ["scanner", "long_header", "long_uri"]
+    );
+    captured
+        .find("Security Event")
+        .unwrap()
+        .assert_field("detail", "nikto");
+}
+
+#[rocket::async_test]
+async fn flags_repeated_auth_failures_at_the_threshold() {
+    let rules = SecurityRules::none().auth_failures(3, Duration::from_secs(60));
+    let (client, captured) = security_client(rules).await;
+
+    for _ in 0..2 {
+        fail(&client, "10.0.0.1:1000", None).await;
+        fail(&client, "10.0.0.2:1000", None).await;
+    }
+    assert!(classifications(&captured).is_empty());
+
+    fail(&client, "10.0.0.1:1000", None).await;
+    let events = captured.find_all("Security Event");
+    assert_eq!(events.len(), 1);
+    events[0].assert_field("classification", "repeated_auth_failures");
+    events[0].assert_field("client_ip", "10.0.0.1");
+    events[0].assert_field("detail", "3 within 60s");
+
+    // counted again from zero after being raised
+    for _ in 0..2 {
+        fail(&client, "10.0.0.1:1000", None).await;
+    }
+    assert_eq!(captured.find_all("Security Event").len(), 1);
+    fail(&client, "10.0.0.1:1000", None).await;
+    assert_eq!(captured.find_all("Security Event").len(), 2);
+}
+
+#[rocket::async_test]
+async fn forgets_auth_failures_older_than_the_window() {
+    let rules = SecurityRules::none().auth_failures(2, Duration::from_millis(200));
+    let (client, captured) = security_client(rules).await;
+
+    fail(&client, "10.0.0.1:1000", None).await;
+    rocket::tokio::time::sleep(Duration::from_millis(250)).await;
+    fail(&client, "10.0.0.1:1000", None).await;
+    assert!(classifications(&captured).is_empty());
+
+    fail(&client, "10.0.0.1:1000", None).await;
+    assert_eq!(classifications(&captured), ["repeated_auth_failures"]);
+}
+
+#[rocket::async_test]
+async fn counts_by_the_remote_address_when_asked() {
+    let rules = SecurityRules::none().auth_failures(3, Duration::from_secs(60));
+    let (client, captured) = security_client(rules).await;
+    for real_ip in ["1.1.1.1", "2.2.2.2", "3.3.3.3"] {
+        fail(&client, "10.0.0.1:1000", Some(real_ip)).await;
+    }
+    assert!(classifications(&captured).is_empty());
+
+    let rules = SecurityRules::none()
+        .auth_failures(3, Duration::from_secs(60))
+        .remote_address(true);
+    let (client, captured) = security_client(rules).await;
+    for real_ip in ["1.1.1.1", "2.2.2.2", "3.3.3.3"] {
+        fail(&client, "10.0.0.1:1000", Some(real_ip)).await;
+    }
+    assert_eq!(classifications(&captured), ["repeated_auth_failures"]);
+}
+
+#[rocket::async_test]
+async fn tracks_at_most_the_max_clients() {
+    let rules = SecurityRules::none()
+        .auth_failures(2, Duration::from_millis(200))
+        .max_tracked_clients(1);
+    let (client, captured) = security_client(rules).await;
+
+    fail(&client, "10.0.0.1:1000", None).await;
+    for _ in 0..2 {
+        fail(&client, "10.0.0.2:1000", None).await;
+    }
+    assert!(classifications(&captured).is_empty());
+
+    // room is made once the tracked client stops failing
+    rocket::tokio::time::sleep(Duration::from_millis(250)).await;
+    for _ in 0..2 {
+        fail(&client, "10.0.0.2:1000", None).await;
+    }
+    captured
+        .find("Security Event")
+        .unwrap()
+        .assert_field("client_ip", "10.0.0.2");
+}
@@ -0,0 +1,165 @@
+#![cfg(all(feature = "syslog", unix))]
+
+use rocket_slogger::syslog::{Facility, SyslogDrain, Transport};
+use rocket_slogger::{info, warn, Drain, Logger, Slogger};
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::net::TcpListener;
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn socket_path(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("rocket-slogger-{}-{name}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn receive(listener: &UnixDatagram) -> String {
+    let mut buffer = vec![0; 65536];
+    let length = listener.recv(&mut buffer).unwrap();
+    String::from_utf8(buffer[..length].to_vec()).unwrap()
+}
+
+#[test]
+fn frames_records_as_rfc5424() {
+    let path = socket_path("rfc5424");
+    let listener = UnixDatagram::bind(&path).unwrap();
+
+    let drain = SyslogDrain::new("my app", Transport::Unix(path.clone()))
+        .unwrap()
+        .hostname("web-1");
+    let logger = Logger::root(drain.fuse(), rocket_slogger::o!("code" => 200));
+
+    info!(logger, "Response"; "uri" => "/");
+    let message = receive(&listener);
+
+    // user facility at informational severity, with spaces dropped from header fields
+    assert!(message.starts_with("<14>1 "), "{message}");
+
+    let parts = message.splitn(7, ' ').collect::<Vec<_>>();
+    assert!(parts[1].ends_with('Z'), "{message}");
+    assert_eq!(parts[2], "web-1");
+    assert_eq!(parts[3], "myapp");
+    assert_eq!(parts[4], std::process::id().to_string());
+    assert_eq!(parts[5], "-");
+    assert_eq!(parts[6], r#"[rocket@32473 uri="/" code="200"] Response"#);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn escapes_structured_data() {
+    let path = socket_path("escapes");
+    let listener = UnixDatagram::bind(&path).unwrap();
+
+    let drain = SyslogDrain::new("app", Transport::Unix(path.clone()))
+        .unwrap()
+        .facility(Facility::Local0)
+        .structured_data_id("custom@12345");
+    let logger = Logger::root(drain.fuse(), rocket_slogger::o!());
+
+    warn!(logger, "Odd"; "a key=\"x\"]" => r#"quote " slash \ bracket ]"#);
+    let message = receive(&listener);
+
+    // local0 at warning severity
+    assert!(message.starts_with("<132>1 "), "{message}");
+    assert!(
+        message.ends_with(r#"[custom@12345 a_key__x__="quote \" slash \\ bracket \]"] Odd"#),
+        "{message}"
+    );
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn frames_records_without_fields_with_a_nil_value() {
+    let path = socket_path("nil");
+    let listener = UnixDatagram::bind(&path).unwrap();
+
+    let drain = SyslogDrain::new("app", Transport::Unix(path.clone())).unwrap();
+    let logger = Logger::root(drain.fuse(), rocket_slogger::o!());
+
+    info!(logger, "Rocket Shutdown");
+    assert!(receive(&listener).ends_with(" - - Rocket Shutdown"));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn octet_counts_records_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let drain = SyslogDrain::new("app", Transport::Tcp(address)).unwrap();
+    let logger = Logger::root(drain.fuse(), rocket_slogger::o!());
+
+    info!(logger, "First");
+    info!(logger, "Second"; "code" => 404);
+
+    let (stream, _) = listener.accept().unwrap();
+    let mut reader = BufReader::new(stream);
+    for expected in ["- First", r#"[rocket@32473 code="404"] Second"#] {
+        let mut length = vec![];
+        reader.read_until(b' ', &mut length).unwrap();
+        let length: usize = String::from_utf8(length).unwrap().trim().parse().unwrap();
+
+        let mut frame = vec![0; length];
+        reader.read_exact(&mut frame).unwrap();
+        let frame = String::from_utf8(frame).unwrap();
+        assert!(frame.starts_with("<14>1 "), "{frame}");
+        assert!(frame.ends_with(expected), "{frame}");
+    }
+}
+
+#[test]
+fn drops_records_that_can_not_be_sent() {
+    // nothing listens on the path
+    let logger =
+        Slogger::new_syslog_logger("app", Transport::Unix(socket_path("missing"))).unwrap();
+
+    info!(logger, "Dropped");
+}
+
+#[test]
+fn waits_before_reconnecting_to_a_server_that_is_down() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let errors = Arc::new(Mutex::new(vec![]));
+    let drain = SyslogDrain::new("app", Transport::Tcp(address)).unwrap();
+    let logger = Logger::root(
+        drain
+            .map_err({
+                let errors = errors.clone();
+                move |error| errors.lock().unwrap().push(error.kind())
+            })
+            .ignore_res(),
+        rocket_slogger::o!(),
+    );
+
+    let (stream, _) = listener.accept().unwrap();
+    drop(stream);
+    drop(listener);
+
+    // writes only fail once the closed connection was noticed
+    for _ in 0..100 {
+        info!(logger, "Lost");
+        if errors.lock().unwrap().contains(&ErrorKind::NotConnected) {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let errors = errors.lock().unwrap();
+    assert!(errors.contains(&ErrorKind::NotConnected), "{errors:?}");
+    assert_eq!(
+        errors
+            .iter()
+            .filter(|error| **error == ErrorKind::ConnectionRefused)
+            .count(),
+        1,
+        "{errors:?}"
+    );
+}
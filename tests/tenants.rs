@@ -0,0 +1,57 @@
+#![cfg(all(feature = "tenants", feature = "testing"))]
+
+mod common;
+
+use rocket::http::Header;
+use rocket::{get, routes};
+use rocket_slogger::tenant::{Tenant, Tenants};
+use rocket_slogger::{debug, FilterLevel, Logger, Slogger};
+
+#[get("/")]
+fn index(log: Slogger) -> &'static str {
+    debug!(log, "Details");
+    "index"
+}
+
+#[rocket::async_test]
+async fn routes_the_records_of_each_tenant() {
+    let (acme_logger, acme) = Slogger::new_capturing();
+    let tenants = Tenants::from_header("X-Tenant")
+        .tenant("acme", Tenant::new().logger(Logger::clone(&acme_logger)))
+        .tenant("quiet", Tenant::new().level(FilterLevel::Warning));
+    let (client, shared) =
+        common::capturing_client(|fairing| fairing.with_tenants(tenants), routes![index]).await;
+    for tenant in ["acme", "quiet", "other"] {
+        client
+            .get("/")
+            .header(Header::new("X-Tenant", tenant))
+            .dispatch()
+            .await;
+    }
+    client.get("/").dispatch().await;
+
+    // every record of the tenant with its own logger goes there
+    let acme_records = acme.records();
+    assert!(acme.find("Details").is_some());
+    for record in &acme_records {
+        record.assert_field("tenant", "acme");
+    }
+    assert!(shared
+        .records()
+        .iter()
+        .all(|record| record.field("tenant") != Some("acme")));
+
+    // the quiet tenant's info and debug records are filtered out
+    assert!(shared
+        .records()
+        .iter()
+        .all(|record| record.field("tenant") != Some("quiet")));
+
+    // tenants without settings and requests without a tenant use the fairing's logger
+    let details = shared.find_all("Details");
+    let tenants = details
+        .iter()
+        .map(|record| record.field("tenant"))
+        .collect::<Vec<_>>();
+    assert_eq!(tenants, [Some("other"), None]);
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "testing")]
+
+mod common;
+
+use rocket::{get, routes};
+use rocket_slogger::{info, log_fields, warn, Level, Slogger};
+
+#[get("/greet/<name>")]
+fn greet(log: Slogger, name: &str) -> &'static str {
+    let log = log.new(log_fields!("name" => "logger", "greeting" => "hello"));
+    info!(log, "Greeted"; "name" => name.to_string());
+    warn!(log, "Greeted"; "name" => "again");
+    "hello"
+}
+
+#[rocket::async_test]
+async fn captures_every_record_with_its_fields() {
+    let (client, captured) = common::capturing_client(|fairing| fairing, routes![greet]).await;
+    assert!(captured.find("Rocket Launched").is_some());
+
+    // leaves only the records of the request
+    captured.clear();
+    client.get("/greet/ann").dispatch().await;
+
+    let messages = captured
+        .records()
+        .into_iter()
+        .map(|record| record.message)
+        .collect::<Vec<_>>();
+    assert_eq!(messages, ["Request", "Greeted", "Greeted", "Response"]);
+
+    // the first with the message, with the fields of the request's logger too
+    let greeted = captured.find("Greeted").unwrap();
+    assert_eq!(greeted.level, Level::Info);
+    greeted.assert_field("greeting", "hello");
+    greeted.assert_field("method", "GET");
+    greeted.assert_field("uri", "/greet/ann");
+    assert_eq!(greeted.field("missing"), None);
+
+    // the record's own value wins over its logger's
+    greeted.assert_field("name", "ann");
+
+    let levels = captured
+        .find_all("Greeted")
+        .into_iter()
+        .map(|record| record.level)
+        .collect::<Vec<_>>();
+    assert_eq!(levels, [Level::Info, Level::Warning]);
+
+    captured.assert_field("name", "again");
+    captured.find("Response").unwrap().assert_field("code", 200);
+    assert!(captured.find("Never Logged").is_none());
+    assert!(captured.find_all("Never Logged").is_empty());
+
+    // records logged after the handle was handed out are still seen
+    client.get("/greet/bob").dispatch().await;
+    captured.assert_field("name", "bob");
+}
+
+#[rocket::async_test]
+#[should_panic(expected = "field `name` of the `Greeted` record")]
+async fn fails_on_a_different_value() {
+    let (client, captured) = common::capturing_client(|fairing| fairing, routes![greet]).await;
+    client.get("/greet/ann").dispatch().await;
+
+    captured
+        .find("Greeted")
+        .unwrap()
+        .assert_field("name", "bob");
+}
+
+#[rocket::async_test]
+#[should_panic(expected = "no record has `name` set to `bob`")]
+async fn fails_when_no_record_has_the_value() {
+    let (client, captured) = common::capturing_client(|fairing| fairing, routes![greet]).await;
+    client.get("/greet/ann").dispatch().await;
+
+    captured.assert_field("name", "bob");
+}
@@ -0,0 +1,51 @@
+#![cfg(all(feature = "tracing", feature = "transactions", feature = "testing"))]
+
+use rocket::local::asynchronous::Client;
+use rocket::{get, routes};
+use rocket_slogger::Slogger;
+
+#[get("/users")]
+fn users() -> &'static str {
+    tracing::info!(page = 1, "Listing Users");
+    "users"
+}
+
+#[get("/orders")]
+fn orders() -> &'static str {
+    let _span = tracing::info_span!("orders", page = 2).entered();
+    tracing::info!("Listing Orders");
+    "orders"
+}
+
+// the subscriber is process wide, so every request is made by the one test
+#[rocket::async_test]
+async fn events_in_routes_are_within_the_request_span() {
+    let (fairing, captured) = Slogger::new_capturing();
+    let rocket = rocket::build()
+        .attach(fairing.with_tracing())
+        .mount("/", routes![users, orders]);
+    let client = Client::untracked(rocket).await.unwrap();
+
+    for uri in ["/users", "/orders"] {
+        client.get(uri).dispatch().await;
+    }
+
+    let requests = captured.find_all("Request");
+    let transaction = |uri: &str| {
+        requests
+            .iter()
+            .find(|record| record.field("uri") == Some(uri))
+            .and_then(|record| record.field("transaction"))
+            .map(str::to_string)
+            .unwrap_or_else(|| panic!("no request record for {uri}: {:#?}", captured.records()))
+    };
+
+    let users = captured.find("Listing Users").unwrap();
+    users.assert_field("transaction", transaction("/users"));
+    users.assert_field("page", 1);
+
+    let orders = captured.find("Listing Orders").unwrap();
+    orders.assert_field("transaction", transaction("/orders"));
+    orders.assert_field("page", 2);
+    assert_ne!(transaction("/users"), transaction("/orders"));
+}